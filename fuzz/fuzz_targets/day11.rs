@@ -0,0 +1,15 @@
+#![no_main]
+
+use advent_of_code_2022::day11;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    // The checked parsers must not panic on any input. The fast solutions may
+    // assume that the input is valid, so they only run on accepted input.
+    if let Ok(answer) = day11::try_part_1(input) {
+        assert_eq!(answer.to_string(), day11::part_1(input).to_string());
+    }
+    if let Ok(answer) = day11::try_part_2(input) {
+        assert_eq!(answer.to_string(), day11::part_2(input).to_string());
+    }
+});
@@ -0,0 +1,303 @@
+use std::{fmt::Display, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::generate;
+use crate::solution::{Day, Timings};
+
+/// How long solving a day took over a number of runs, from parsing until both
+/// answers are known.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DayTimes {
+    pub day: u8,
+    pub median: Duration,
+    pub p95: Duration,
+    /// The median of each phase on its own.
+    pub phases: Timings,
+}
+
+/// The run times of every day that was benchmarked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub runs: usize,
+    pub days: Vec<DayTimes>,
+}
+
+/// The value below which `fraction` of the sorted durations fall, using the
+/// nearest rank.
+fn percentile(sorted: &[Duration], fraction: f64) -> Duration {
+    let rank = (fraction * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Solve a day `runs` times, after one run to warm up.
+pub fn measure(day: &dyn Day, input: &str, runs: usize) -> DayTimes {
+    assert!(runs > 0, "there must be at least one run");
+    day.solve_timed(input);
+    let timings: Vec<Timings> = (0..runs).map(|_| day.solve_timed(input).1).collect();
+    let sorted = |phase: fn(&Timings) -> Duration| {
+        let mut durations: Vec<Duration> = timings.iter().map(phase).collect();
+        durations.sort_unstable();
+        durations
+    };
+    let durations = sorted(|timings| timings.parse + timings.parts.iter().sum::<Duration>());
+    DayTimes {
+        day: day.number(),
+        median: percentile(&durations, 0.5),
+        p95: percentile(&durations, 0.95),
+        phases: Timings {
+            parse: percentile(&sorted(|timings| timings.parse), 0.5),
+            parts: [
+                percentile(&sorted(|timings| timings.parts[0]), 0.5),
+                percentile(&sorted(|timings| timings.parts[1]), 0.5),
+            ],
+        },
+    }
+}
+
+/// How long a day took on generated input of one size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeTimes {
+    /// The size passed to the generator, whose meaning depends on the day.
+    pub size: usize,
+    /// The length of the generated input.
+    pub bytes: usize,
+    pub times: DayTimes,
+}
+
+impl SizeTimes {
+    /// The median time per byte of input, in nanoseconds.
+    pub fn nanoseconds_per_byte(&self) -> f64 {
+        self.times.median.as_nanos() as f64 / self.bytes.max(1) as f64
+    }
+}
+
+/// Time a day on input generated from `seed` at each of the `sizes`, to see
+/// how it scales.
+pub fn measure_sizes(day: &dyn Day, seed: u64, sizes: &[usize], runs: usize) -> Vec<SizeTimes> {
+    sizes
+        .iter()
+        .map(|&size| {
+            let input =
+                generate::generate(day.number(), seed, size).expect("every day has a generator");
+            SizeTimes {
+                size,
+                bytes: input.len(),
+                times: measure(day, &input, runs),
+            }
+        })
+        .collect()
+}
+
+/// A day whose median got slower than the baseline allows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Regression {
+    pub day: u8,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl Regression {
+    /// How much slower the day got, in percent.
+    pub fn percentage(&self) -> f64 {
+        (self.current.as_secs_f64() / self.baseline.as_secs_f64() - 1.0) * 100.0
+    }
+}
+
+impl Display for Regression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day{} regressed by {:.1}%: {:?} -> {:?}",
+            self.day,
+            self.percentage(),
+            self.baseline,
+            self.current
+        )
+    }
+}
+
+impl Report {
+    /// The sum of the medians of all days.
+    pub fn total_median(&self) -> Duration {
+        self.days.iter().map(|times| times.median).sum()
+    }
+
+    /// The part of the total median that a day takes up, between 0 and 1.
+    pub fn share(&self, times: &DayTimes) -> f64 {
+        times.median.as_secs_f64() / self.total_median().as_secs_f64()
+    }
+
+    /// The days whose median is more than `threshold` percent slower than in
+    /// the baseline. Days missing from the baseline are not compared.
+    pub fn regressions(&self, baseline: &Report, threshold: f64) -> Vec<Regression> {
+        self.days
+            .iter()
+            .filter_map(|current| {
+                let baseline = baseline
+                    .days
+                    .iter()
+                    .find(|times| times.day == current.day)?;
+                let regression = Regression {
+                    day: current.day,
+                    baseline: baseline.median,
+                    current: current.median,
+                };
+                (regression.percentage() > threshold).then_some(regression)
+            })
+            .collect()
+    }
+
+    /// The report as JSON, with durations in nanoseconds.
+    pub fn to_json(&self) -> String {
+        let nanoseconds = |duration: Duration| duration.as_nanos() as u64;
+        let json = ReportJson {
+            runs: self.runs,
+            total_median_ns: nanoseconds(self.total_median()),
+            days: self
+                .days
+                .iter()
+                .map(|times| DayTimesJson {
+                    day: times.day,
+                    median_ns: nanoseconds(times.median),
+                    p95_ns: nanoseconds(times.p95),
+                    parse_median_ns: nanoseconds(times.phases.parse),
+                    part_1_median_ns: nanoseconds(times.phases.parts[0]),
+                    part_2_median_ns: nanoseconds(times.phases.parts[1]),
+                    share: self.share(times),
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&json).expect("reports are valid JSON")
+    }
+
+    /// Read a report written by `to_json`. Derived fields are ignored, and
+    /// the phases may be missing from older reports.
+    pub fn from_json(json: &str) -> serde_json::Result<Report> {
+        let json: ReportJson = serde_json::from_str(json)?;
+        Ok(Report {
+            runs: json.runs,
+            days: json
+                .days
+                .iter()
+                .map(|times| DayTimes {
+                    day: times.day,
+                    median: Duration::from_nanos(times.median_ns),
+                    p95: Duration::from_nanos(times.p95_ns),
+                    phases: Timings {
+                        parse: Duration::from_nanos(times.parse_median_ns),
+                        parts: [
+                            Duration::from_nanos(times.part_1_median_ns),
+                            Duration::from_nanos(times.part_2_median_ns),
+                        ],
+                    },
+                })
+                .collect(),
+        })
+    }
+}
+
+/// A report as it is stored in a baseline file.
+#[derive(Serialize, Deserialize)]
+struct ReportJson {
+    runs: usize,
+    #[serde(skip_deserializing)]
+    total_median_ns: u64,
+    days: Vec<DayTimesJson>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DayTimesJson {
+    day: u8,
+    median_ns: u64,
+    p95_ns: u64,
+    #[serde(default)]
+    parse_median_ns: u64,
+    #[serde(default)]
+    part_1_median_ns: u64,
+    #[serde(default)]
+    part_2_median_ns: u64,
+    #[serde(skip_deserializing)]
+    share: f64,
+}
+
+#[test]
+fn test_percentile() {
+    let durations: Vec<_> = (1..=20).map(Duration::from_millis).collect();
+    assert_eq!(percentile(&durations, 0.5), Duration::from_millis(10));
+    assert_eq!(percentile(&durations, 0.95), Duration::from_millis(19));
+    assert_eq!(percentile(&durations, 1.0), Duration::from_millis(20));
+    assert_eq!(percentile(&durations[..1], 0.5), Duration::from_millis(1));
+}
+
+#[test]
+fn test_measure() {
+    let day = crate::solution::day(6).unwrap();
+    let times = measure(day, "mjqjpqmgbljsphdztnvjfqwrcgsmlb", 5);
+    assert_eq!(times.day, 6);
+    assert!(times.median <= times.p95);
+    assert!(times.phases.parse <= times.p95);
+}
+
+#[test]
+fn test_measure_sizes() {
+    let day = crate::solution::day(1).unwrap();
+    let sizes = measure_sizes(day, 0, &[10, 100], 3);
+    assert_eq!(sizes.len(), 2);
+    assert_eq!((sizes[0].size, sizes[1].size), (10, 100));
+    assert!(sizes[0].bytes < sizes[1].bytes);
+    assert!(sizes.iter().all(|size| size.times.day == 1));
+    assert!(sizes[1].nanoseconds_per_byte() > 0.0);
+}
+
+#[test]
+fn test_report() {
+    let millis = Duration::from_millis;
+    let report = Report {
+        runs: 10,
+        days: vec![
+            DayTimes {
+                day: 1,
+                median: millis(1),
+                p95: millis(2),
+                phases: Timings {
+                    parse: Duration::from_micros(200),
+                    parts: [Duration::from_micros(300), Duration::from_micros(500)],
+                },
+            },
+            DayTimes {
+                day: 2,
+                median: millis(3),
+                p95: millis(3),
+                phases: Timings::default(),
+            },
+        ],
+    };
+    assert_eq!(report.total_median(), millis(4));
+    assert_eq!(report.share(&report.days[1]), 0.75);
+    assert_eq!(Report::from_json(&report.to_json()).unwrap(), report);
+    assert!(report.to_json().contains("\"share\": 0.75"));
+    assert!(Report::from_json("{\"runs\": 1}").is_err());
+    assert!(Report::from_json(
+        "{\"runs\": 1, \"days\": [{\"day\": 1, \"median_ns\": -5, \"p95_ns\": 6}]}"
+    )
+    .is_err());
+    let without_phases = "{\"runs\": 1, \"days\": [{\"day\": 1, \"median_ns\": 5, \"p95_ns\": 6}]}";
+    assert_eq!(
+        Report::from_json(without_phases).unwrap().days[0].phases,
+        Timings::default()
+    );
+
+    let mut slower = report.clone();
+    slower.days[0].median = millis(2);
+    slower.days[1].median = Duration::from_micros(3200);
+    assert_eq!(slower.regressions(&report, 10.0).len(), 1);
+    assert_eq!(slower.regressions(&report, 10.0)[0].day, 1);
+    assert_eq!(slower.regressions(&report, 5.0).len(), 2);
+    assert_eq!(slower.regressions(&report, 100.0), []);
+    assert_eq!(report.regressions(&slower, 0.0), []);
+    assert_eq!(
+        slower.regressions(&report, 10.0)[0].to_string(),
+        "day1 regressed by 100.0%: 1ms -> 2ms"
+    );
+}
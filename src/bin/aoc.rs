@@ -0,0 +1,397 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use advent_of_code_2022::{
+    bench::{self, Report},
+    generate,
+    run::{self, PartResult},
+    solution::{self, DAYS},
+    verify::{self, Answers, Outcome},
+};
+
+const USAGE: &str = "\
+usage: aoc DAY [- | --input FILE] [--format text|json]
+       aoc run [--inputs DIR] [--format text|json]
+       aoc verify [--answers FILE] [--inputs DIR]
+       aoc generate DAY SIZE [--seed N]
+       aoc bench-all [--runs N] [--inputs DIR] [--baseline FILE] [--threshold PERCENT]
+                     [--save-baseline]
+       aoc bench-all --generate SIZE,SIZE,... [--seed N] [--runs N]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.split_first() {
+        Some((day, options)) if day.parse::<u8>().is_ok() => run_single(day, options),
+        Some((command, options)) if command == "run" => run_run(options),
+        Some((command, options)) if command == "verify" => run_verify(options),
+        Some((command, options)) if command == "generate" => run_generate(options),
+        Some((command, options)) if command == "bench-all" => run_bench_all(options),
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Solve every day against every input file, and print the answers with how
+/// long they took.
+fn run_run(options: &[String]) -> ExitCode {
+    let mut input_dir = PathBuf::from("input/2022");
+    let mut json = false;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let value = options.next();
+        match (option.as_str(), value.map(String::as_str)) {
+            ("--inputs", Some(value)) => input_dir = value.into(),
+            ("--format", Some("text")) => json = false,
+            ("--format", Some("json")) => json = true,
+            (_, None) => {
+                eprintln!("missing value for `{option}`\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+            _ => {
+                eprintln!("unknown option `{option}`\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let mut results = Vec::new();
+    for day in DAYS {
+        match run::run_day(&input_dir, day) {
+            Ok(day_results) => results.extend(day_results),
+            Err(error) => {
+                eprintln!("could not read input: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    print_results(&results, json);
+    ExitCode::SUCCESS
+}
+
+/// Solve one day against its puzzle input, another file, or standard input
+/// if the input is `-`.
+fn run_single(day: &str, options: &[String]) -> ExitCode {
+    let Some(day) = day.parse().ok().and_then(solution::day) else {
+        eprintln!("there is no day {day}");
+        return ExitCode::FAILURE;
+    };
+    let mut input = format!("input/2022/day{}.txt", day.number());
+    let mut json = false;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        if option == "-" {
+            input = option.clone();
+            continue;
+        }
+        let value = options.next();
+        match (option.as_str(), value.map(String::as_str)) {
+            ("--input", Some(value)) => input = value.to_string(),
+            ("--format", Some("text")) => json = false,
+            ("--format", Some("json")) => json = true,
+            (_, None) => {
+                eprintln!("missing value for `{option}`\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+            _ => {
+                eprintln!("unknown option `{option}`\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let text = if input == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text).map(|_| text)
+    } else {
+        fs::read_to_string(&input)
+    };
+    let text = match text {
+        Ok(text) => text,
+        Err(error) => {
+            eprintln!("could not read {input}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match run::try_run_input(day, &input, &text) {
+        Ok(results) => {
+            print_results(&results, json);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{input}: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Print the results as a table, or as JSON.
+fn print_results(results: &[PartResult], json: bool) {
+    if json {
+        println!("{}", run::to_json(results));
+        return;
+    }
+    for result in results {
+        // Multi-line answers are shown below, unless they can be read.
+        let answer = match result.text() {
+            Some(text) => text,
+            None if result.answer.contains('\n') => format!("\n{}", result.answer.trim_end()),
+            None => result.answer.clone(),
+        };
+        println!(
+            "day{:<2} part {} {:<30} {:>10} {answer}",
+            result.day,
+            result.part,
+            result.input,
+            format!("{:.1?}", result.parse + result.duration),
+        );
+    }
+}
+
+/// Solve every day against every input file, and report whether the answers
+/// match the recorded ones.
+fn run_verify(options: &[String]) -> ExitCode {
+    let mut answers_path = PathBuf::from("answers/2022.toml");
+    let mut input_dir = PathBuf::from("input/2022");
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let target = match option.as_str() {
+            "--answers" => &mut answers_path,
+            "--inputs" => &mut input_dir,
+            _ => {
+                eprintln!("unknown option `{option}`\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        };
+        match options.next() {
+            Some(value) => *target = value.into(),
+            None => {
+                eprintln!("missing value for `{option}`\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let answers = match Answers::load(&answers_path) {
+        Ok(answers) => answers,
+        Err(error) => {
+            eprintln!("could not load answers: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let verifications = match verify::verify(&answers, &input_dir) {
+        Ok(verifications) => verifications,
+        Err(error) => {
+            eprintln!("could not read input: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for verification in &verifications {
+        let status = match &verification.outcome {
+            Outcome::Pass => {
+                passed += 1;
+                "pass".to_string()
+            }
+            Outcome::Fail { expected } => {
+                failed += 1;
+                format!("FAIL: expected {expected:?}, got {:?}", verification.answer)
+            }
+            Outcome::Missing => {
+                missing += 1;
+                format!("missing, got {:?}", verification.answer)
+            }
+        };
+        println!(
+            "day{:<2} part {} {:<20} {status}",
+            verification.day, verification.part, verification.input
+        );
+    }
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Print random input for a day, of the given size.
+fn run_generate(options: &[String]) -> ExitCode {
+    let (day, size, seed) = match options {
+        [day, size] => (day, size, "0"),
+        [day, size, option, seed] if option == "--seed" => (day, size, seed.as_str()),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let (Ok(day), Ok(size), Ok(seed)) = (day.parse(), size.parse(), seed.parse()) else {
+        eprintln!("DAY, SIZE and the seed must be numbers\n{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    match generate::generate(day, seed, size) {
+        Some(input) => {
+            print!("{input}");
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!("there is no day {day}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Time every day on its puzzle input, and compare the medians with those in
+/// the baseline file, if there is one. With `--generate`, time every day on
+/// generated input of each size instead.
+fn run_bench_all(options: &[String]) -> ExitCode {
+    let mut runs = 100;
+    let mut sizes: Vec<usize> = Vec::new();
+    let mut seed = 0;
+    let mut input_dir = PathBuf::from("input/2022");
+    let mut baseline_path = PathBuf::from("target/bench-baseline.json");
+    let mut threshold = 10.0;
+    let mut save_baseline = false;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        if option == "--save-baseline" {
+            save_baseline = true;
+            continue;
+        }
+        let Some(value) = options.next() else {
+            eprintln!("missing value for `{option}`\n{USAGE}");
+            return ExitCode::FAILURE;
+        };
+        let valid = match option.as_str() {
+            "--runs" => value.parse().map(|value| runs = value).is_ok() && runs > 0,
+            "--inputs" => {
+                input_dir = value.into();
+                true
+            }
+            "--baseline" => {
+                baseline_path = value.into();
+                true
+            }
+            "--threshold" => value.parse().map(|value| threshold = value).is_ok(),
+            "--generate" => {
+                let parsed: Result<Vec<_>, _> = value.split(',').map(str::parse).collect();
+                parsed.map(|value| sizes = value).is_ok()
+            }
+            "--seed" => value.parse().map(|value| seed = value).is_ok(),
+            _ => {
+                eprintln!("unknown option `{option}`\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        };
+        if !valid {
+            eprintln!("invalid value `{value}` for `{option}`\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if !sizes.is_empty() {
+        if save_baseline {
+            eprintln!("`--save-baseline` cannot be combined with `--generate`\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+        bench_sizes(&sizes, seed, runs);
+        return ExitCode::SUCCESS;
+    }
+
+    let mut report = Report {
+        runs,
+        days: Vec::new(),
+    };
+    for day in DAYS {
+        let path = input_dir.join(format!("day{}.txt", day.number()));
+        match fs::read_to_string(&path) {
+            Ok(input) => report.days.push(bench::measure(day, &input, runs)),
+            Err(error) => eprintln!("skipping day{}: {}: {error}", day.number(), path.display()),
+        }
+    }
+    for times in &report.days {
+        println!(
+            "day{:<2} median {:>12} p95 {:>12} {:>5.1}%  parse {:>12} part 1 {:>12} part 2 {:>12}",
+            times.day,
+            format!("{:.1?}", times.median),
+            format!("{:.1?}", times.p95),
+            100.0 * report.share(times),
+            format!("{:.1?}", times.phases.parse),
+            format!("{:.1?}", times.phases.parts[0]),
+            format!("{:.1?}", times.phases.parts[1]),
+        );
+    }
+    println!(
+        "total median {:.1?} over {runs} runs",
+        report.total_median()
+    );
+
+    if save_baseline {
+        let saved = baseline_path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&baseline_path, format!("{}\n", report.to_json())));
+        return match saved {
+            Ok(()) => {
+                println!("saved the baseline to {}", baseline_path.display());
+                ExitCode::SUCCESS
+            }
+            Err(error) => {
+                eprintln!("could not save the baseline: {error}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let Ok(baseline) = fs::read_to_string(&baseline_path) else {
+        println!(
+            "no baseline at {}, save one with --save-baseline",
+            baseline_path.display()
+        );
+        return ExitCode::SUCCESS;
+    };
+    let baseline = match Report::from_json(&baseline) {
+        Ok(baseline) => baseline,
+        Err(error) => {
+            eprintln!(
+                "{} is not a valid baseline: {error}",
+                baseline_path.display()
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    let regressions = report.regressions(&baseline, threshold);
+    for regression in &regressions {
+        println!("{regression}");
+    }
+    if regressions.is_empty() {
+        println!("no day regressed by more than {threshold}%");
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Time every day on generated input of each size, and show how the time per
+/// byte changes as the input grows.
+fn bench_sizes(sizes: &[usize], seed: u64, runs: usize) {
+    for day in DAYS {
+        for size in bench::measure_sizes(day, seed, sizes, runs) {
+            println!(
+                "day{:<2} size {:>8} {:>10} bytes median {:>12} p95 {:>12} {:>8.2}ns/byte",
+                day.number(),
+                size.size,
+                size.bytes,
+                format!("{:.1?}", size.times.median),
+                format!("{:.1?}", size.times.p95),
+                size.nanoseconds_per_byte(),
+            );
+        }
+    }
+}
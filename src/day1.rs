@@ -1,3 +1,11 @@
+use std::fmt::{self, Display};
+
+use primitive_types::U256;
+use serde::Serialize;
+
+use crate::solution::Solution;
+use crate::util::{normalize_input, ParseError, ParseErrorKind, Scanner};
+
 /// Parse a number from an iterator, up to the first newline. Returns `None` if
 /// no digits are found.
 fn parse_number(it: &mut impl Iterator<Item = u8>) -> Option<usize> {
@@ -11,7 +19,7 @@ fn parse_number(it: &mut impl Iterator<Item = u8>) -> Option<usize> {
 }
 
 /// Parse all numbers from input.
-fn parse_numbers(s: &str) -> impl Iterator<Item = Option<usize>> + '_ {
+pub fn parse_numbers(s: &str) -> impl Iterator<Item = Option<usize>> + '_ {
     let mut iter = s.as_bytes().iter().copied().peekable();
     std::iter::from_fn(move || {
         if iter.peek().is_some() {
@@ -22,7 +30,7 @@ fn parse_numbers(s: &str) -> impl Iterator<Item = Option<usize>> + '_ {
     })
 }
 
-fn totals(s: &str) -> impl Iterator<Item = usize> + '_ {
+pub fn totals(s: &str) -> impl Iterator<Item = usize> + '_ {
     let mut numbers = parse_numbers(s);
     std::iter::from_fn(move ||
         // Take numbers up to the first `None`, unwrap Option
@@ -31,8 +39,35 @@ fn totals(s: &str) -> impl Iterator<Item = usize> + '_ {
         .fold(None, |acc, d| Some(acc.unwrap_or_default() + d)))
 }
 
-fn sum_of_max<const AMOUNT: usize>(input: &str) -> usize {
-    totals(input)
+/// Parse the totals of all elves, returning an error if the input is
+/// malformed.
+pub fn try_totals(s: &str) -> Result<Vec<usize>, ParseError> {
+    let mut scanner = Scanner::new(s);
+    let mut totals = Vec::new();
+    let mut current = None;
+    while !scanner.is_at_end() {
+        let start = scanner.position();
+        if scanner.peek() == Some(b'\n') {
+            // An empty line ends the inventory of an elf
+            match current.take() {
+                Some(total) => totals.push(total),
+                None => return Err(scanner.invalid_at(start, "empty inventory")),
+            }
+        } else {
+            let calories = scanner.number::<usize>()?;
+            let total: &mut usize = current.get_or_insert(0);
+            *total = total
+                .checked_add(calories)
+                .ok_or_else(|| scanner.invalid_at(start, "total out of range"))?;
+        }
+        scanner.line_end()?;
+    }
+    totals.extend(current);
+    Ok(totals)
+}
+
+fn sum_of_max_totals<const AMOUNT: usize>(totals: impl Iterator<Item = usize>) -> usize {
+    totals
         .fold([0usize; AMOUNT], |mut acc: [usize; AMOUNT], mut new| {
             for max in acc.iter_mut() {
                 if new > *max {
@@ -45,12 +80,442 @@ fn sum_of_max<const AMOUNT: usize>(input: &str) -> usize {
         .sum()
 }
 
+/// An integer type to sum calories in. Wider types can sum inputs whose
+/// totals do not fit in a `usize`.
+pub trait Calories: Copy + Ord + Display {
+    const ZERO: Self;
+
+    /// Append a decimal digit, or return `None` if the result does not fit.
+    fn push_digit(self, digit: u8) -> Option<Self>;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_calories {
+    ($($t:ty),*) => {$(
+        impl Calories for $t {
+            const ZERO: Self = 0;
+
+            fn push_digit(self, digit: u8) -> Option<Self> {
+                self.checked_mul(10)?.checked_add(<$t>::from(digit))
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+        }
+    )*};
+}
+
+impl_calories!(usize, u128);
+
+impl Calories for U256 {
+    const ZERO: Self = U256::zero();
+
+    fn push_digit(self, digit: u8) -> Option<Self> {
+        self.checked_mul(U256::from(10))?
+            .checked_add(U256::from(digit))
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        U256::checked_add(self, other)
+    }
+}
+
+/// Calories that did not fit in the type they were summed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// An item or the total of an elf, counting elves from 0 and lines from
+    /// 1.
+    Elf { elf: usize, line: usize },
+    /// The sum of the largest totals.
+    Sum,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Overflow::Elf { elf, line } => {
+                write!(f, "the total of elf {elf} overflows on line {line}")
+            }
+            Overflow::Sum => write!(f, "the sum of the largest totals overflows"),
+        }
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// The calories on a line of digits, if they fit in `T`.
+fn checked_calories<T: Calories>(line: &str) -> Option<T> {
+    line.bytes()
+        .try_fold(T::ZERO, |acc, digit| acc.push_digit(digit & 0b1111))
+}
+
+/// The totals of all elves, summed in `T`, for input that is well-formed but
+/// may have numbers of any size.
+pub fn checked_totals<T: Calories>(s: &str) -> Result<Vec<T>, Overflow> {
+    let mut totals = Vec::new();
+    let mut total = None;
+    for (index, line) in s.lines().enumerate() {
+        if line.is_empty() {
+            totals.extend(total.take());
+            continue;
+        }
+        let overflow = Overflow::Elf {
+            elf: totals.len(),
+            line: index + 1,
+        };
+        let calories = checked_calories::<T>(line).ok_or(overflow)?;
+        total = Some(
+            total
+                .unwrap_or(T::ZERO)
+                .checked_add(calories)
+                .ok_or(overflow)?,
+        );
+    }
+    totals.extend(total);
+    Ok(totals)
+}
+
+/// The sum of the `amount` largest totals, if it fits.
+fn checked_sum_of_max<T: Calories>(totals: &[T], amount: usize) -> Result<T, Overflow> {
+    let mut totals = totals.to_vec();
+    totals.sort_unstable_by(|a, b| b.cmp(a));
+    totals
+        .iter()
+        .take(amount)
+        .try_fold(T::ZERO, |sum, &total| sum.checked_add(total))
+        .ok_or(Overflow::Sum)
+}
+
+/// Part 1, summing in `T` and reporting overflow instead of wrapping.
+pub fn checked_part_1<T: Calories>(input: &str) -> Result<T, Overflow> {
+    let input = &normalize_input(input);
+    checked_sum_of_max(&checked_totals(input)?, 1)
+}
+
+/// Part 2, summing in `T` and reporting overflow instead of wrapping.
+pub fn checked_part_2<T: Calories>(input: &str) -> Result<T, Overflow> {
+    let input = &normalize_input(input);
+    checked_sum_of_max(&checked_totals(input)?, 3)
+}
+
+/// The `n` elves carrying the most calories, as pairs of the index of the elf
+/// and its total, from most to fewest calories. Elves with equal totals keep
+/// their order. The totals are read once, keeping only the best `n` so far.
+pub fn top_elves(totals: impl IntoIterator<Item = usize>, n: usize) -> Vec<(usize, usize)> {
+    let mut top: Vec<(usize, usize)> = Vec::new();
+    if n == 0 {
+        return top;
+    }
+    for (elf, total) in totals.into_iter().enumerate() {
+        if top.len() == n {
+            if top[n - 1].1 >= total {
+                continue;
+            }
+            top.pop();
+        }
+        let position = top.partition_point(|&(_, other)| other >= total);
+        top.insert(position, (elf, total));
+    }
+    top
+}
+
+/// All elves as pairs of the index of the elf and its total, from most to
+/// fewest calories. Elves with equal totals keep their order.
+pub fn ranking(totals: impl IntoIterator<Item = usize>) -> Vec<(usize, usize)> {
+    let mut ranking: Vec<_> = totals.into_iter().enumerate().collect();
+    ranking.sort_by(|(_, a), (_, b)| b.cmp(a));
+    ranking
+}
+
+/// The items carried by a single elf.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ElfStats {
+    /// The index of the elf in the input.
+    pub elf: usize,
+    pub items: usize,
+    pub total: usize,
+    pub min: usize,
+    pub max: usize,
+}
+
+impl ElfStats {
+    /// The average calories of an item.
+    pub fn mean(&self) -> f64 {
+        self.total as f64 / self.items as f64
+    }
+}
+
+/// A range of totals and the number of elves whose total falls in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Bucket {
+    pub start: usize,
+    /// The end of the range, which is included, so that a range can end at
+    /// `usize::MAX`.
+    pub end: usize,
+    pub elves: usize,
+}
+
+/// Statistics about the inventories of all elves. Elves without items are
+/// left out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElfInventory {
+    pub elves: Vec<ElfStats>,
+    /// The totals of all elves, from fewest to most calories.
+    sorted: Vec<usize>,
+}
+
+impl ElfInventory {
+    /// The number of buckets used when the inventory is shown or written as
+    /// JSON.
+    pub const HISTOGRAM_BUCKETS: usize = 10;
+
+    /// Read the statistics of all elves, going over the input once. Fails
+    /// if an item or the total of an elf does not fit in a `usize`.
+    pub fn new(input: &str) -> Result<Self, Overflow> {
+        let input = normalize_input(input);
+        let mut elves = Vec::new();
+        let mut current: Option<ElfStats> = None;
+        for (index, line) in input.lines().enumerate() {
+            if line.is_empty() {
+                elves.extend(current.take());
+                continue;
+            }
+            let overflow = Overflow::Elf {
+                elf: elves.len(),
+                line: index + 1,
+            };
+            let calories: usize = checked_calories(line).ok_or(overflow)?;
+            match &mut current {
+                Some(stats) => {
+                    stats.items += 1;
+                    stats.total = stats.total.checked_add(calories).ok_or(overflow)?;
+                    stats.min = stats.min.min(calories);
+                    stats.max = stats.max.max(calories);
+                }
+                None => {
+                    current = Some(ElfStats {
+                        elf: elves.len(),
+                        items: 1,
+                        total: calories,
+                        min: calories,
+                        max: calories,
+                    })
+                }
+            }
+        }
+        elves.extend(current);
+        let mut sorted: Vec<usize> = elves.iter().map(|stats| stats.total).collect();
+        sorted.sort_unstable();
+        Ok(ElfInventory { elves, sorted })
+    }
+
+    /// The total below which `fraction` of the totals fall, using the nearest
+    /// rank, or `None` if there are no elves.
+    pub fn percentile(&self, fraction: f64) -> Option<usize> {
+        let rank = (fraction * self.sorted.len() as f64).ceil() as usize;
+        self.sorted
+            .get(rank.clamp(1, self.sorted.len().max(1)) - 1)
+            .copied()
+    }
+
+    pub fn median(&self) -> Option<usize> {
+        self.percentile(0.5)
+    }
+
+    /// The average total of an elf.
+    pub fn mean(&self) -> f64 {
+        // Summed as floats, since the sum of the totals may not fit in a
+        // `usize`.
+        self.sorted.iter().map(|&total| total as f64).sum::<f64>() / self.sorted.len() as f64
+    }
+
+    /// Split the totals into at most `buckets` ranges of equal width, from
+    /// the fewest to the most calories. The last range ends at the largest
+    /// total.
+    pub fn histogram(&self, buckets: usize) -> Vec<Bucket> {
+        let (Some(&min), Some(&max)) = (self.sorted.first(), self.sorted.last()) else {
+            return Vec::new();
+        };
+        // Widths and ends are worked out in `u128`, since with totals near
+        // `usize::MAX` they do not fit in a `usize`.
+        let width = (max - min) as u128 / buckets.max(1) as u128 + 1;
+        let bucket = |start: usize, elves| Bucket {
+            start,
+            end: (start as u128 + width - 1).min(max as u128) as usize,
+            elves,
+        };
+        let mut histogram: Vec<Bucket> = Vec::new();
+        for &total in &self.sorted {
+            let start = min + ((total - min) as u128 / width * width) as usize;
+            match histogram.last_mut() {
+                Some(last) if last.start == start => last.elves += 1,
+                _ => {
+                    // Fill the gap with empty buckets. The gap ends before
+                    // `start`, so the next start fits in a `usize`.
+                    let mut next = histogram.last().map_or(min, |last| last.end + 1);
+                    while next < start {
+                        histogram.push(bucket(next, 0));
+                        next += width as usize;
+                    }
+                    histogram.push(bucket(start, 1));
+                }
+            }
+        }
+        histogram
+    }
+
+    /// The statistics as JSON, with an entry for every elf and a summary of
+    /// all totals.
+    pub fn to_json(&self) -> String {
+        #[derive(Serialize)]
+        struct ElfJson<'a> {
+            #[serde(flatten)]
+            stats: &'a ElfStats,
+            mean: f64,
+        }
+
+        #[derive(Serialize)]
+        struct InventoryJson<'a> {
+            elves: Vec<ElfJson<'a>>,
+            mean: f64,
+            median: Option<usize>,
+            p90: Option<usize>,
+            p99: Option<usize>,
+            histogram: Vec<Bucket>,
+        }
+
+        let json = InventoryJson {
+            elves: self
+                .elves
+                .iter()
+                .map(|stats| ElfJson {
+                    stats,
+                    mean: stats.mean(),
+                })
+                .collect(),
+            mean: self.mean(),
+            median: self.median(),
+            p90: self.percentile(0.9),
+            p99: self.percentile(0.99),
+            histogram: self.histogram(Self::HISTOGRAM_BUCKETS),
+        };
+        serde_json::to_string_pretty(&json).expect("statistics are valid JSON")
+    }
+}
+
+/// A table with a row for every elf, followed by a summary of all totals.
+impl Display for ElfInventory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>5} {:>5} {:>8} {:>7} {:>7} {:>9}",
+            "elf", "items", "total", "min", "max", "mean"
+        )?;
+        for stats in &self.elves {
+            writeln!(
+                f,
+                "{:>5} {:>5} {:>8} {:>7} {:>7} {:>9.1}",
+                stats.elf,
+                stats.items,
+                stats.total,
+                stats.min,
+                stats.max,
+                stats.mean()
+            )?;
+        }
+        let (Some(median), Some(p90), Some(p99)) =
+            (self.median(), self.percentile(0.9), self.percentile(0.99))
+        else {
+            return Ok(());
+        };
+        writeln!(
+            f,
+            "\nmean {:.1}, median {median}, p90 {p90}, p99 {p99}\n",
+            self.mean()
+        )?;
+        for bucket in self.histogram(Self::HISTOGRAM_BUCKETS) {
+            let row = format!(
+                "{:>8}..={:<8} {:>5} {}",
+                bucket.start,
+                bucket.end,
+                bucket.elves,
+                "#".repeat(bucket.elves)
+            );
+            writeln!(f, "{}", row.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// The total calories carried by each elf.
+pub fn parse(input: &str) -> Vec<usize> {
+    totals(input).collect()
+}
+
+pub fn solve_part_1(totals: &[usize]) -> usize {
+    sum_of_max_totals::<1>(totals.iter().copied())
+}
+
+pub fn solve_part_2(totals: &[usize]) -> usize {
+    sum_of_max_totals::<3>(totals.iter().copied())
+}
+
 pub fn part_1(input: &str) -> usize {
-    sum_of_max::<1>(input)
+    let input = &normalize_input(input);
+    solve_part_1(&parse(input))
 }
 
 pub fn part_2(input: &str) -> usize {
-    sum_of_max::<3>(input)
+    let input = &normalize_input(input);
+    solve_part_2(&parse(input))
+}
+
+pub fn try_part_1(input: &str) -> Result<usize, ParseError> {
+    let input = &normalize_input(input);
+    Ok(solve_part_1(&try_totals(input)?))
+}
+
+pub fn try_part_2(input: &str) -> Result<usize, ParseError> {
+    let input = &normalize_input(input);
+    // Every total fits, but their sum may not
+    checked_sum_of_max(&try_totals(input)?, 3).map_err(|_| {
+        ParseError::at(
+            input,
+            0,
+            ParseErrorKind::Invalid("sum of totals out of range"),
+        )
+    })
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    type Parsed<'a> = Vec<usize>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Vec<usize> {
+        parse(input)
+    }
+
+    fn part_1(totals: &Vec<usize>) -> usize {
+        solve_part_1(totals)
+    }
+
+    fn part_2(totals: &Vec<usize>) -> usize {
+        solve_part_2(totals)
+    }
+
+    fn try_part_1(input: &str) -> Result<usize, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<usize, ParseError> {
+        try_part_2(input)
+    }
 }
 
 #[test]
@@ -102,3 +567,252 @@ fn test_part_2() {
     let input = include_str!("../input/2022/day1.txt");
     assert_eq!(part_2(input), 204610);
 }
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(1);
+}
+
+#[test]
+fn test_try_parts() {
+    let input = include_str!("../input/2022/day1.txt");
+    assert_eq!(try_part_1(input), Ok(part_1(input)));
+    assert_eq!(try_part_2(input), Ok(part_2(input)));
+    assert_eq!(
+        try_part_1("1000\n2000\n\n30x0\n").unwrap_err().to_string(),
+        "line 4, column 3: expected newline, found 'x'"
+    );
+    assert_eq!(
+        try_part_1("1000\r2000\n").unwrap_err().to_string(),
+        "line 1, column 5: expected newline, found '\\r'"
+    );
+    assert_eq!(try_part_1("1000\r\n2000\r\n"), Ok(3000));
+    assert_eq!(
+        try_part_1("1000\n\n\n2000\n").unwrap_err().to_string(),
+        "line 3, column 1: empty inventory"
+    );
+    assert_eq!(
+        try_part_1("18446744073709551615\n1\n")
+            .unwrap_err()
+            .to_string(),
+        "line 2, column 1: total out of range"
+    );
+}
+
+#[test]
+fn test_top_elves() {
+    let input = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+    let totals = parse(input);
+    assert_eq!(
+        top_elves(totals.iter().copied(), 3),
+        [(3, 24000), (2, 11000), (4, 10000)]
+    );
+    assert_eq!(top_elves(totals.iter().copied(), 0), []);
+    assert_eq!(
+        top_elves(totals.iter().copied(), 10),
+        ranking(totals.clone())
+    );
+    assert_eq!(
+        ranking(totals),
+        [(3, 24000), (2, 11000), (4, 10000), (0, 6000), (1, 4000)]
+    );
+    assert_eq!(top_elves([5, 7, 5, 7], 3), [(1, 7), (3, 7), (0, 5)]);
+
+    let input = include_str!("../input/2022/day1.txt");
+    let totals = parse(input);
+    let ranking = ranking(totals.iter().copied());
+    for n in [1, 3, 10, totals.len()] {
+        assert_eq!(top_elves(totals.iter().copied(), n), ranking[..n]);
+    }
+    let top_3: usize = top_elves(totals, 3).iter().map(|(_, total)| total).sum();
+    assert_eq!(top_3, part_2(input));
+}
+
+#[test]
+fn test_inventory() {
+    let inventory =
+        ElfInventory::new("1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n\n10000\n").unwrap();
+    assert_eq!(
+        inventory.elves[0],
+        ElfStats {
+            elf: 0,
+            items: 3,
+            total: 6000,
+            min: 1000,
+            max: 3000
+        }
+    );
+    assert_eq!(inventory.elves[0].mean(), 2000.0);
+    assert_eq!(inventory.elves.len(), 4);
+    assert_eq!(inventory.elves[3].elf, 3);
+    assert_eq!(inventory.median(), Some(6000));
+    assert_eq!(inventory.percentile(0.9), Some(11000));
+    assert_eq!(inventory.mean(), 7750.0);
+    assert_eq!(
+        inventory.histogram(2),
+        [
+            Bucket {
+                start: 4000,
+                end: 7500,
+                elves: 2
+            },
+            Bucket {
+                start: 7501,
+                end: 11000,
+                elves: 2
+            }
+        ]
+    );
+    assert_eq!(inventory.histogram(10).len(), 10);
+    assert_eq!(
+        inventory
+            .histogram(10)
+            .iter()
+            .map(|bucket| bucket.elves)
+            .collect::<Vec<_>>(),
+        [1, 0, 1, 0, 0, 0, 0, 0, 1, 1]
+    );
+    assert_eq!(
+        inventory.to_string().lines().nth(1),
+        Some("    0     3     6000    1000    3000    2000.0")
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&inventory.to_json()).unwrap();
+    assert_eq!(json["median"], 6000);
+    assert_eq!(json["elves"].as_array().map(Vec::len), Some(4));
+    assert_eq!(json["elves"][0]["total"], 6000);
+    assert_eq!(json["elves"][0]["mean"], 2000.0);
+    assert_eq!(json["histogram"][0]["elves"], 1);
+
+    let empty = ElfInventory::new("").unwrap();
+    assert_eq!(empty.median(), None);
+    assert_eq!(empty.histogram(10), []);
+    let json: serde_json::Value = serde_json::from_str(&empty.to_json()).unwrap();
+    assert_eq!(json["median"], serde_json::Value::Null);
+
+    let input = include_str!("../input/2022/day1.txt");
+    let inventory = ElfInventory::new(input).unwrap();
+    assert_eq!(inventory.percentile(1.0), Some(part_1(input)));
+    assert_eq!(
+        inventory
+            .histogram(ElfInventory::HISTOGRAM_BUCKETS)
+            .iter()
+            .map(|bucket| bucket.elves)
+            .sum::<usize>(),
+        inventory.elves.len()
+    );
+
+    // Buckets can end at the largest possible total
+    let inventory = ElfInventory::new("0\n\n18446744073709551615\n").unwrap();
+    let histogram = inventory.histogram(1);
+    assert_eq!(
+        histogram,
+        [Bucket {
+            start: 0,
+            end: usize::MAX,
+            elves: 2
+        }]
+    );
+    let histogram = inventory.histogram(ElfInventory::HISTOGRAM_BUCKETS);
+    assert_eq!(histogram.len(), ElfInventory::HISTOGRAM_BUCKETS);
+    assert_eq!(histogram.last().map(|bucket| bucket.end), Some(usize::MAX));
+    assert!(inventory.to_string().ends_with(" 1 #\n"));
+    let inventory = ElfInventory::new("18446744073709551614\n\n18446744073709551615\n").unwrap();
+    assert_eq!(inventory.histogram(10).len(), 2);
+    assert!(!inventory.to_json().is_empty());
+
+    // Totals that only fit together in a `usize` give a large mean, but no
+    // overflow
+    let half = usize::MAX / 2;
+    let inventory = ElfInventory::new(&format!("{half}\n\n{half}\n")).unwrap();
+    assert_eq!(inventory.mean(), half as f64);
+    assert_eq!(
+        ElfInventory::new(&format!("1\n\n{half}\n{half}\n2\n")),
+        Err(Overflow::Elf { elf: 1, line: 5 })
+    );
+    assert_eq!(
+        ElfInventory::new("1\n\n18446744073709551616000\n"),
+        Err(Overflow::Elf { elf: 1, line: 3 })
+    );
+}
+
+#[test]
+fn test_checked_parts() {
+    let input = include_str!("../input/2022/day1.txt");
+    assert_eq!(checked_part_1::<usize>(input), Ok(part_1(input)));
+    assert_eq!(checked_part_2::<usize>(input), Ok(part_2(input)));
+    assert_eq!(checked_part_2::<u128>(input), Ok(part_2(input) as u128));
+    assert_eq!(checked_part_2::<U256>(input), Ok(U256::from(part_2(input))));
+
+    let huge = "18446744073709551615\n\n1\n18446744073709551615\n\n1\n";
+    assert_eq!(
+        checked_part_1::<usize>(huge),
+        Err(Overflow::Elf { elf: 1, line: 4 })
+    );
+    assert_eq!(
+        Overflow::Elf { elf: 1, line: 4 }.to_string(),
+        "the total of elf 1 overflows on line 4"
+    );
+    assert_eq!(checked_part_1::<u128>(huge), Ok(u128::from(u64::MAX) + 1));
+    assert_eq!(
+        checked_part_2::<u128>(huge),
+        Ok(2 * u128::from(u64::MAX) + 2)
+    );
+    assert_eq!(
+        checked_part_2::<usize>("18446744073709551615\n\n1\n"),
+        Err(Overflow::Sum)
+    );
+    assert_eq!(
+        try_part_2("18446744073709551615\n\n1\n")
+            .unwrap_err()
+            .to_string(),
+        "line 1, column 1: sum of totals out of range"
+    );
+    assert_eq!(try_part_1("18446744073709551615\n\n1\n"), Ok(usize::MAX));
+
+    let digits = "9".repeat(40);
+    assert_eq!(
+        checked_part_1::<u128>(&format!("1\n\n{digits}\n")),
+        Err(Overflow::Elf { elf: 1, line: 3 })
+    );
+    assert_eq!(
+        checked_part_1::<U256>(&format!("1\n\n{digits}\n")),
+        Ok(U256::from_dec_str(&digits).unwrap())
+    );
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy(
+        "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000",
+    );
+    assert_eq!(part_1(&input), 24000);
+    assert_eq!(part_2(&input), 45000);
+    assert_eq!(try_part_1(&input), Ok(24000));
+}
@@ -1,5 +1,8 @@
 use std::fmt::Display;
 
+use crate::solution::Solution;
+use crate::util::{normalize_input, ParseError, Scanner};
+
 const CRT_SIZE: usize = 240;
 pub struct Crt([bool; CRT_SIZE]);
 
@@ -19,6 +22,13 @@ impl Display for Crt {
     }
 }
 
+impl Crt {
+    /// The letters on the screen, if it shows any.
+    pub fn text(&self) -> Option<String> {
+        crate::ocr::read_letters(&self.to_string())
+    }
+}
+
 impl<I: Iterator<Item = bool>> From<I> for Crt {
     fn from(iter: I) -> Self {
         let mut arr = [false; CRT_SIZE];
@@ -29,14 +39,14 @@ impl<I: Iterator<Item = bool>> From<I> for Crt {
     }
 }
 
-#[derive(Debug)]
-enum Instruction {
+#[derive(Debug, Copy, Clone)]
+pub enum Instruction {
     Noop,
     AddX(i8),
 }
 
 #[derive(Copy, Clone)]
-struct Cpu {
+pub struct Cpu {
     x: i8,
 }
 
@@ -47,12 +57,12 @@ impl Default for Cpu {
 }
 
 impl Cpu {
-    fn register(self) -> i8 {
+    pub fn register(self) -> i8 {
         self.x
     }
 
     /// Execute instruction. Returns how many instructions it took.
-    fn execute_instruction(&mut self, inst: Instruction) -> usize {
+    pub fn execute_instruction(&mut self, inst: Instruction) -> usize {
         match inst {
             Instruction::Noop => 1,
             Instruction::AddX(a) => {
@@ -62,13 +72,13 @@ impl Cpu {
         }
     }
 
-    fn execute_cycles(&mut self, inst: Instruction) -> impl Iterator<Item = Cpu> {
+    pub fn execute_cycles(&mut self, inst: Instruction) -> impl Iterator<Item = Cpu> {
         let cpu = *self;
         let times = self.execute_instruction(inst);
-        std::iter::repeat(cpu).take(times)
+        std::iter::repeat_n(cpu, times)
     }
 
-    fn execute_all_cycles<'a>(
+    pub fn execute_all_cycles<'a>(
         &'a mut self,
         instructions: impl Iterator<Item = Instruction> + 'a,
     ) -> impl Iterator<Item = Cpu> + 'a {
@@ -92,7 +102,7 @@ fn parse_num(s: &[u8]) -> i8 {
     }
 }
 
-fn parse_instructions(s: &str) -> impl Iterator<Item = Instruction> + '_ {
+pub fn parse_instructions(s: &str) -> impl Iterator<Item = Instruction> + '_ {
     s.split_terminator('\n')
         .map(str::as_bytes)
         .map(|inst| match inst[0] {
@@ -102,9 +112,109 @@ fn parse_instructions(s: &str) -> impl Iterator<Item = Instruction> + '_ {
         })
 }
 
+pub fn try_parse_instructions(s: &str) -> Result<Vec<Instruction>, ParseError> {
+    let mut scanner = Scanner::new(s);
+    let mut instructions = Vec::new();
+    let mut cpu = Cpu::default();
+    while !scanner.is_at_end() {
+        let line_start = scanner.position();
+        let instruction = match scanner.peek() {
+            Some(b'n') => {
+                scanner.tag("noop")?;
+                Instruction::Noop
+            }
+            Some(b'a') => {
+                scanner.tag("addx ")?;
+                // The fast parser reads at most two digits after the sign.
+                let start = scanner.position();
+                let negative = scanner.peek() == Some(b'-');
+                if negative {
+                    scanner.tag("-")?;
+                }
+                let magnitude: i8 = scanner.number()?;
+                if magnitude > 99 {
+                    return Err(scanner.invalid_at(start, "number out of range"));
+                }
+                Instruction::AddX(if negative { -magnitude } else { magnitude })
+            }
+            _ => return Err(scanner.expected("noop or addx")),
+        };
+        if let Instruction::AddX(value) = instruction {
+            cpu.x = cpu
+                .x
+                .checked_add(value)
+                .ok_or_else(|| scanner.invalid_at(line_start, "register out of range"))?;
+        }
+        instructions.push(instruction);
+        scanner.line_end()?;
+    }
+    Ok(instructions)
+}
+
+pub fn parse(s: &str) -> Vec<Instruction> {
+    parse_instructions(s).collect()
+}
+
+pub fn solve_part_1(instructions: &[Instruction]) -> isize {
+    signal_strength(instructions.iter().copied())
+}
+
+pub fn solve_part_2(instructions: &[Instruction]) -> Crt {
+    draw(instructions.iter().copied())
+}
+
 pub fn part_1(s: &str) -> isize {
+    let s = &normalize_input(s);
+    solve_part_1(&parse(s))
+}
+
+pub fn part_2(s: &str) -> Crt {
+    let s = &normalize_input(s);
+    solve_part_2(&parse(s))
+}
+
+pub fn try_part_1(s: &str) -> Result<isize, ParseError> {
+    let s = &normalize_input(s);
+    Ok(solve_part_1(&try_parse_instructions(s)?))
+}
+
+pub fn try_part_2(s: &str) -> Result<Crt, ParseError> {
+    let s = &normalize_input(s);
+    Ok(solve_part_2(&try_parse_instructions(s)?))
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    type Parsed<'a> = Vec<Instruction>;
+    type Answer1 = isize;
+    type Answer2 = Crt;
+
+    fn parse(input: &str) -> Vec<Instruction> {
+        parse(input)
+    }
+
+    fn part_1(instructions: &Vec<Instruction>) -> isize {
+        solve_part_1(instructions)
+    }
+
+    fn part_2(instructions: &Vec<Instruction>) -> Crt {
+        solve_part_2(instructions)
+    }
+
+    fn try_part_1(input: &str) -> Result<isize, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<Crt, ParseError> {
+        try_part_2(input)
+    }
+}
+
+fn signal_strength(instructions: impl Iterator<Item = Instruction>) -> isize {
     Cpu::default()
-        .execute_all_cycles(parse_instructions(s))
+        .execute_all_cycles(instructions)
         .map(Cpu::register)
         .enumerate()
         .skip(19)
@@ -114,12 +224,12 @@ pub fn part_1(s: &str) -> isize {
         .sum()
 }
 
-pub fn part_2(s: &str) -> Crt {
+fn draw(instructions: impl Iterator<Item = Instruction>) -> Crt {
     Cpu::default()
-        .execute_all_cycles(parse_instructions(s))
+        .execute_all_cycles(instructions)
         .map(Cpu::register)
         .zip((0i8..40).cycle())
-        .map(|(value, crt_index)| value <= crt_index + 1 && value + 1 >= crt_index)
+        .map(|(value, crt_index)| (i16::from(value) - i16::from(crt_index)).abs() <= 1)
         .into()
 }
 
@@ -301,6 +411,7 @@ fn test_part_2_example() {
 #######.......#######.......#######.....
 "
     );
+    assert_eq!(part_2(EXAMPLE_INPUT).text(), None);
 }
 
 #[test]
@@ -323,4 +434,47 @@ fn test_part_2() {
 #....####..###.#....#..#.#....####.###..
 "
     );
+    assert_eq!(part_2(input).text().as_deref(), Some("PZGPKPEB"));
+}
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(10);
+}
+
+#[test]
+fn test_try_parts() {
+    let input = include_str!("../input/2022/day10.txt");
+    assert_eq!(try_part_1(input), Ok(part_1(input)));
+    assert_eq!(
+        try_part_2(input).unwrap().to_string(),
+        part_2(input).to_string()
+    );
+    assert_eq!(
+        try_part_1("noop\naddx -99\naddx 100\n")
+            .unwrap_err()
+            .to_string(),
+        "line 3, column 6: number out of range"
+    );
+    assert_eq!(
+        try_part_1("addx 99\naddx 27\naddx 1\n")
+            .unwrap_err()
+            .to_string(),
+        "line 3, column 1: register out of range"
+    );
+    assert_eq!(
+        try_part_1("noop\nadd 3\n").unwrap_err().to_string(),
+        "line 2, column 4: expected \"addx \", found ' '"
+    );
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy(EXAMPLE_INPUT);
+    assert_eq!(part_1(&input), 13140);
+    assert_eq!(
+        part_2(&input).to_string(),
+        part_2(EXAMPLE_INPUT).to_string()
+    );
+    assert_eq!(try_part_1(&input), Ok(13140));
 }
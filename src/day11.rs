@@ -1,3 +1,8 @@
+use arrayvec::ArrayVec;
+
+use crate::solution::Solution;
+use crate::util::{normalize_input, ParseError, ParseErrorKind};
+
 #[derive(Copy, Clone, Debug)]
 enum Operation {
     Add(u64),
@@ -22,6 +27,15 @@ impl Operation {
             Operation::Square => *num *= *num,
         };
     }
+
+    /// Like `apply`, but returns `None` if the worry level overflows.
+    fn checked_apply(self, num: u64) -> Option<u64> {
+        match self {
+            Operation::Add(a) => num.checked_add(a),
+            Operation::Mul(a) => num.checked_mul(a),
+            Operation::Square => num.checked_mul(num),
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -29,35 +43,42 @@ struct Test(u64);
 
 impl Test {
     fn test(self, num: u64) -> bool {
-        num % self.0 == 0
+        num.is_multiple_of(self.0)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Item(u64);
-
-#[derive(Debug)]
-struct Monkey {
+#[derive(Debug, Clone)]
+pub struct Monkey {
     operation: Operation,
     test: Test,
     monkey_condition: [usize; 2],
 }
 
-const MAX_ITEMS: usize = 32;
+/// The most items an input can have. Every monkey has room for all of them,
+/// as they may all be thrown to the same monkey.
+pub const MAX_ITEMS: usize = 64;
 
-mod parse {
+pub mod parse {
     use arrayvec::ArrayVec;
 
-    use crate::util;
+    use crate::util::{self, ParseError, Scanner};
 
     use super::{Monkey, Operation, Test, MAX_ITEMS};
 
+    /// Skip `n` bytes, which must be there.
+    fn skip(bytes: &mut impl Iterator<Item = u8>, n: usize) {
+        #[cfg(feature = "nightly")]
+        bytes.advance_by(n).unwrap();
+        #[cfg(not(feature = "nightly"))]
+        bytes.nth(n - 1).unwrap();
+    }
+
     fn read_monkey_line(bytes: &mut impl Iterator<Item = u8>) {
-        bytes.advance_by("Monkey _:\n".len()).unwrap();
+        skip(bytes, "Monkey _:\n".len());
     }
 
     fn read_starting_items(bytes: &mut impl Iterator<Item = u8>) -> ArrayVec<u64, MAX_ITEMS> {
-        bytes.advance_by("  Starting items: ".len()).unwrap();
+        skip(bytes, "  Starting items: ".len());
         let mut numbers = ArrayVec::new();
         loop {
             numbers.push(util::read_two_digit_number(bytes).into());
@@ -71,13 +92,13 @@ mod parse {
     }
 
     fn read_operation(bytes: &mut impl Iterator<Item = u8>) -> Operation {
-        bytes.advance_by("  Operation: new = old ".len()).unwrap();
+        skip(bytes, "  Operation: new = old ".len());
         match bytes.next().unwrap() {
             b'*' => {
                 bytes.next();
                 let mut num = bytes.next().unwrap();
                 if num == b'o' {
-                    bytes.advance_by("ld\n".len()).unwrap();
+                    skip(bytes, "ld\n".len());
                     Operation::Square
                 } else {
                     num &= 0b1111;
@@ -100,22 +121,18 @@ mod parse {
     }
 
     fn read_test(bytes: &mut impl Iterator<Item = u8>) -> (Test, [usize; 2]) {
-        bytes.advance_by("  Test: divisible by ".len()).unwrap();
+        skip(bytes, "  Test: divisible by ".len());
         let test = Test(util::read_number_one_or_two_digits(bytes).into());
-        bytes
-            .advance_by("    If true: throw to monkey ".len())
-            .unwrap();
+        skip(bytes, "    If true: throw to monkey ".len());
         let true_monkey = bytes.next().unwrap() & 0b1111;
-        bytes
-            .advance_by("\n    If false: throw to monkey ".len())
-            .unwrap();
+        skip(bytes, "\n    If false: throw to monkey ".len());
         let false_monkey = bytes.next().unwrap() & 0b1111;
         let monkey_condition = [false_monkey.into(), true_monkey.into()];
         bytes.next();
         (test, monkey_condition)
     }
 
-    pub(super) fn parse_monkeys<const AMOUNT: usize>(
+    pub fn parse_monkeys<const AMOUNT: usize>(
         s: &str,
     ) -> [(Monkey, ArrayVec<u64, MAX_ITEMS>); AMOUNT] {
         let mut bytes = s.as_bytes().iter().copied();
@@ -135,66 +152,274 @@ mod parse {
             )
         })
     }
+
+    pub type Monkeys<const AMOUNT: usize> = [(Monkey, ArrayVec<u64, MAX_ITEMS>); AMOUNT];
+
+    /// Read the index of the monkey an item is thrown to.
+    fn try_read_target<const AMOUNT: usize>(
+        scanner: &mut Scanner,
+        monkey: usize,
+    ) -> Result<usize, ParseError> {
+        let position = scanner.position();
+        match scanner.number()? {
+            target if target == monkey => {
+                Err(scanner.invalid_at(position, "monkey throws to itself"))
+            }
+            target if target < AMOUNT => Ok(target),
+            _ => Err(scanner.invalid_at(position, "no such monkey")),
+        }
+    }
+
+    /// Like `parse_monkeys`, but returns an error if the input is malformed
+    /// or if the monkeys could not be simulated.
+    pub fn try_parse_monkeys<const AMOUNT: usize>(s: &str) -> Result<Monkeys<AMOUNT>, ParseError> {
+        let mut scanner = Scanner::new(s);
+        let mut monkeys = ArrayVec::<_, AMOUNT>::new();
+        let mut total_items = 0;
+        for index in 0..AMOUNT {
+            if index > 0 {
+                scanner.tag("\n")?;
+            }
+            scanner.tag("Monkey ")?;
+            let position = scanner.position();
+            if scanner.number::<usize>()? != index {
+                return Err(scanner.invalid_at(position, "monkeys are not numbered in order"));
+            }
+            scanner.tag(":\n  Starting items: ")?;
+            let mut items = ArrayVec::new();
+            loop {
+                // All items can end up with the same monkey.
+                let position = scanner.position();
+                if total_items == MAX_ITEMS {
+                    return Err(scanner.invalid_at(position, "too many items"));
+                }
+                total_items += 1;
+                // The fast parser reads items of exactly two digits.
+                match scanner.number()? {
+                    item @ 10..=99 => items.push(item),
+                    _ => return Err(scanner.invalid_at(position, "items must have two digits")),
+                }
+                if scanner.peek() != Some(b',') {
+                    break;
+                }
+                scanner.tag(", ")?;
+            }
+            scanner.tag("\n  Operation: new = old ")?;
+            let operator = scanner.one_of(b"*+", "* or +")?;
+            scanner.tag(" ")?;
+            let position = scanner.position();
+            let operation = match (operator, scanner.peek()) {
+                (b'*', Some(b'o')) => {
+                    scanner.tag("old")?;
+                    Operation::Square
+                }
+                (b'+', Some(b'o')) => {
+                    return Err(scanner.invalid_at(position, "unsupported operation"))
+                }
+                (b'*', _) => Operation::Mul(scanner.small_number()?.into()),
+                _ => Operation::Add(scanner.small_number()?.into()),
+            };
+            scanner.tag("\n  Test: divisible by ")?;
+            let position = scanner.position();
+            let test = match scanner.small_number()? {
+                0 => return Err(scanner.invalid_at(position, "division by zero")),
+                divisor => Test(divisor.into()),
+            };
+            scanner.tag("\n    If true: throw to monkey ")?;
+            let true_monkey = try_read_target::<AMOUNT>(&mut scanner, index)?;
+            scanner.tag("\n    If false: throw to monkey ")?;
+            let false_monkey = try_read_target::<AMOUNT>(&mut scanner, index)?;
+            scanner.line_end()?;
+            monkeys.push((
+                Monkey {
+                    monkey_condition: [false_monkey, true_monkey],
+                    operation,
+                    test,
+                },
+                items,
+            ));
+        }
+        if !scanner.is_at_end() {
+            return Err(scanner.expected("end of input"));
+        }
+        Ok(monkeys
+            .into_inner()
+            .unwrap_or_else(|_| unreachable!("all monkeys were parsed")))
+    }
+}
+
+/// The most monkeys an input can have.
+pub const MAX_MONKEYS: usize = 10;
+
+/// Count the monkeys in the input, without parsing them.
+pub fn monkey_count(s: &str) -> usize {
+    memchr::memmem::find_iter(s.as_bytes(), b"Monkey").count()
+}
+
+/// Evaluate `$body` with the const `$n` set to `$count`, so that it can be
+/// used as the number of monkeys. Counts outside of `2..=MAX_MONKEYS` are
+/// clamped, leaving it to the parser to report the mismatch.
+macro_rules! with_monkey_count {
+    ($count:expr, $n:ident => $body:expr) => {
+        match $count {
+            ..=2 => with_monkey_count!(@ 2, $n, $body),
+            3 => with_monkey_count!(@ 3, $n, $body),
+            4 => with_monkey_count!(@ 4, $n, $body),
+            5 => with_monkey_count!(@ 5, $n, $body),
+            6 => with_monkey_count!(@ 6, $n, $body),
+            7 => with_monkey_count!(@ 7, $n, $body),
+            8 => with_monkey_count!(@ 8, $n, $body),
+            9 => with_monkey_count!(@ 9, $n, $body),
+            _ => with_monkey_count!(@ 10, $n, $body),
+        }
+    };
+    (@ $value:literal, $n:ident, $body:expr) => {{
+        const $n: usize = $value;
+        $body
+    }};
+}
+
+pub fn parse(s: &str) -> Vec<(Monkey, ArrayVec<u64, MAX_ITEMS>)> {
+    with_monkey_count!(monkey_count(s), N => parse::parse_monkeys::<N>(s).into())
+}
+
+/// Copy the parsed monkeys, so that they can be simulated.
+fn monkey_array<const MONKEY_COUNT: usize>(
+    monkeys: &[(Monkey, ArrayVec<u64, MAX_ITEMS>)],
+) -> [(Monkey, ArrayVec<u64, MAX_ITEMS>); MONKEY_COUNT] {
+    core::array::from_fn(|index| monkeys[index].clone())
+}
+
+pub fn solve_part_1(monkeys: &[(Monkey, ArrayVec<u64, MAX_ITEMS>)]) -> usize {
+    with_monkey_count!(monkeys.len(), N => simulate_part_1::<N>(monkey_array(monkeys)))
+}
+
+pub fn solve_part_2(monkeys: &[(Monkey, ArrayVec<u64, MAX_ITEMS>)]) -> usize {
+    with_monkey_count!(monkeys.len(), N => simulate_part_2::<N>(monkey_array(monkeys)))
 }
 
 pub fn part_1(s: &str) -> usize {
-    part_1_generic::<8>(s)
+    let s = &normalize_input(s);
+    solve_part_1(&parse(s))
 }
 
 pub fn part_2(s: &str) -> usize {
-    part_2_generic::<8>(s)
+    let s = &normalize_input(s);
+    solve_part_2(&parse(s))
 }
 
 const PART_ONE_ROUNDS: usize = 20;
 const PART_TWO_ROUNDS: usize = 10_000;
 
-fn part_1_generic<const MONKEY_COUNT: usize>(s: &str) -> usize {
-    let mut monkeys: [_; MONKEY_COUNT] = parse::parse_monkeys(s);
-    let mut inspections = [0usize; MONKEY_COUNT];
-    for _ in 0..PART_ONE_ROUNDS {
-        for (monkey_index, inspection_count) in
-            inspections.iter_mut().enumerate().take(monkeys.len())
-        {
-            while let Some((monkey, mut item)) = {
-                let (monkey, items) = monkeys.get_mut(monkey_index).unwrap();
-                items.pop_at(0).map(|item| (monkey, item))
-            } {
-                *inspection_count += 1;
-                monkey.operation.apply_with_division(&mut item);
-                if monkey.test.test(item) {
-                    monkeys[monkey.monkey_condition[1]].1.push(item);
-                } else {
-                    monkeys[monkey.monkey_condition[0]].1.push(item);
-                }
-            }
-        }
+/// The error for monkeys whose worry levels do not fit in a `u64`.
+fn overflow_error(s: &str) -> ParseError {
+    ParseError::at(s, 0, ParseErrorKind::Invalid("worry levels overflow"))
+}
+
+pub fn try_part_1(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    with_monkey_count!(monkey_count(s), N => {
+        simulate(parse::try_parse_monkeys::<N>(s)?, PART_ONE_ROUNDS, |monkey, item| {
+            Some(monkey.operation.checked_apply(item)? / 3)
+        })
+        .ok_or_else(|| overflow_error(s))
+    })
+}
+
+pub fn try_part_2(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    with_monkey_count!(monkey_count(s), N => {
+        let monkeys = parse::try_parse_monkeys::<N>(s)?;
+        let common_multiple = monkeys
+            .iter()
+            .try_fold(1u64, |product, monkey| product.checked_mul(monkey.0.test.0))
+            .ok_or_else(|| overflow_error(s))?;
+        simulate(monkeys, PART_TWO_ROUNDS, |monkey, item| {
+            Some(monkey.operation.checked_apply(item)? % common_multiple)
+        })
+        .ok_or_else(|| overflow_error(s))
+    })
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    type Parsed<'a> = Vec<(Monkey, ArrayVec<u64, MAX_ITEMS>)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Vec<(Monkey, ArrayVec<u64, MAX_ITEMS>)> {
+        parse(input)
     }
-    let mut max_inspections = [0usize; 2];
-    for mut monkey_inspection in inspections {
-        for existing_max in max_inspections.iter_mut() {
-            if *existing_max < monkey_inspection {
-                std::mem::swap(existing_max, &mut monkey_inspection);
-            }
-        }
+
+    fn part_1(monkeys: &Vec<(Monkey, ArrayVec<u64, MAX_ITEMS>)>) -> usize {
+        solve_part_1(monkeys)
+    }
+
+    fn part_2(monkeys: &Vec<(Monkey, ArrayVec<u64, MAX_ITEMS>)>) -> usize {
+        solve_part_2(monkeys)
+    }
+
+    fn try_part_1(input: &str) -> Result<usize, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<usize, ParseError> {
+        try_part_2(input)
     }
-    max_inspections.iter().product()
 }
 
-fn part_2_generic<const MONKEY_COUNT: usize>(s: &str) -> usize {
-    let mut monkeys: [_; MONKEY_COUNT] = parse::parse_monkeys(s);
-    let mut inspections = [0usize; MONKEY_COUNT];
+pub fn part_1_generic<const MONKEY_COUNT: usize>(s: &str) -> usize {
+    let s = &normalize_input(s);
+    simulate_part_1(parse::parse_monkeys::<MONKEY_COUNT>(s))
+}
+
+pub fn part_2_generic<const MONKEY_COUNT: usize>(s: &str) -> usize {
+    let s = &normalize_input(s);
+    simulate_part_2(parse::parse_monkeys::<MONKEY_COUNT>(s))
+}
+
+pub fn simulate_part_1<const MONKEY_COUNT: usize>(
+    monkeys: [(Monkey, ArrayVec<u64, MAX_ITEMS>); MONKEY_COUNT],
+) -> usize {
+    simulate(monkeys, PART_ONE_ROUNDS, |monkey, mut item| {
+        monkey.operation.apply_with_division(&mut item);
+        Some(item)
+    })
+    .unwrap_or_else(|| unreachable!("inspections always succeed"))
+}
+
+pub fn simulate_part_2<const MONKEY_COUNT: usize>(
+    monkeys: [(Monkey, ArrayVec<u64, MAX_ITEMS>); MONKEY_COUNT],
+) -> usize {
     let common_multiple: u64 = monkeys.iter().map(|monkey| monkey.0.test.0).product();
-    for _ in 0..PART_TWO_ROUNDS {
+    simulate(monkeys, PART_TWO_ROUNDS, |monkey, mut item| {
+        monkey.operation.apply(&mut item);
+        Some(item % common_multiple)
+    })
+    .unwrap_or_else(|| unreachable!("inspections always succeed"))
+}
+
+/// Simulate the rounds, where `inspect` gives the new worry level of an item,
+/// and return the product of the two largest inspection counts. Returns
+/// `None` as soon as an inspection does.
+fn simulate<const MONKEY_COUNT: usize>(
+    mut monkeys: [(Monkey, ArrayVec<u64, MAX_ITEMS>); MONKEY_COUNT],
+    rounds: usize,
+    inspect: impl Fn(&Monkey, u64) -> Option<u64>,
+) -> Option<usize> {
+    let mut inspections = [0usize; MONKEY_COUNT];
+    for _ in 0..rounds {
         for (monkey_index, inspection_count) in
             inspections.iter_mut().enumerate().take(monkeys.len())
         {
-            while let Some((monkey, mut item)) = {
+            while let Some((monkey, item)) = {
                 let (monkey, items) = monkeys.get_mut(monkey_index).unwrap();
                 items.pop_at(0).map(|item| (monkey, item))
             } {
                 *inspection_count += 1;
-                monkey.operation.apply(&mut item);
-                item %= common_multiple;
+                let item = inspect(monkey, item)?;
                 if monkey.test.test(item) {
                     monkeys[monkey.monkey_condition[1]].1.push(item);
                 } else {
@@ -211,7 +436,7 @@ fn part_2_generic<const MONKEY_COUNT: usize>(s: &str) -> usize {
             }
         }
     }
-    max_inspections.iter().product()
+    Some(max_inspections.iter().product())
 }
 
 #[cfg(test)]
@@ -265,3 +490,108 @@ fn test_part_2() {
     let input = include_str!("../input/2022/day11.txt");
     assert_eq!(part_2(input), 11741456163);
 }
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(11);
+}
+
+#[test]
+fn test_try_parts() {
+    let input = include_str!("../input/2022/day11.txt");
+    assert_eq!(try_part_1(input), Ok(part_1(input)));
+    assert_eq!(
+        simulate_part_1(parse::try_parse_monkeys::<4>(EXAMPLE_INPUT).unwrap()),
+        10605
+    );
+    assert_eq!(
+        simulate_part_2(parse::try_parse_monkeys::<4>(EXAMPLE_INPUT).unwrap()),
+        2713310158
+    );
+}
+
+#[test]
+fn test_monkey_count() {
+    assert_eq!(monkey_count(EXAMPLE_INPUT), 4);
+    assert_eq!(part_1(EXAMPLE_INPUT), 10605);
+    assert_eq!(try_part_2(EXAMPLE_INPUT), Ok(2713310158));
+    let eleven: Vec<_> = (0..11)
+        .map(|monkey| {
+            format!(
+                "Monkey {monkey}:
+  Starting items: 10
+  Operation: new = old + 1
+  Test: divisible by 2
+    If true: throw to monkey {}
+    If false: throw to monkey {}
+",
+                (monkey + 1) % 10,
+                (monkey + 2) % 10
+            )
+        })
+        .collect();
+    let eleven = eleven.join("\n");
+    assert_eq!(monkey_count(&eleven), 11);
+    assert_eq!(
+        try_part_1(&eleven).unwrap_err().to_string(),
+        "line 70, column 1: expected end of input, found '\\n'"
+    );
+}
+
+#[test]
+fn test_try_parse_errors() {
+    let error = |input: &str| {
+        parse::try_parse_monkeys::<4>(input)
+            .map(|_| ())
+            .unwrap_err()
+            .to_string()
+    };
+    assert_eq!(
+        error(&EXAMPLE_INPUT.replace("divisible by 13", "divisible by 0")),
+        "line 18, column 22: division by zero"
+    );
+    assert_eq!(
+        error(&EXAMPLE_INPUT.replace("throw to monkey 3", "throw to monkey 4")),
+        "line 6, column 31: no such monkey"
+    );
+    assert_eq!(
+        error(&EXAMPLE_INPUT.replace("old + 6", "old - 6")),
+        "line 10, column 24: expected * or +, found '-'"
+    );
+    assert_eq!(
+        error(&EXAMPLE_INPUT.replace("\n\nMonkey 3", "\nMonkey 3")),
+        "line 21, column 1: expected \"\\n\", found 'M'"
+    );
+    assert_eq!(
+        error(&EXAMPLE_INPUT[..EXAMPLE_INPUT.len() - 30]),
+        "line 27, column 2: expected \"\\n    If false: throw to monkey \", found end of input"
+    );
+    assert_eq!(
+        error(&EXAMPLE_INPUT.replace("items: 74", "items: 7")),
+        "line 23, column 19: items must have two digits"
+    );
+    assert_eq!(
+        error(&EXAMPLE_INPUT.replace("old + 6", "old + 100")),
+        "line 10, column 26: number out of range"
+    );
+    assert_eq!(
+        error(&EXAMPLE_INPUT.replace("54, 65, 75, 74", &["54"; 70].join(", "))),
+        "line 9, column 267: too many items"
+    );
+}
+
+#[test]
+fn test_worry_level_overflow() {
+    let input = EXAMPLE_INPUT.replace("old + 3", "old * old");
+    assert_eq!(
+        try_part_1(&input).unwrap_err().to_string(),
+        "line 1, column 1: worry levels overflow"
+    );
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy(EXAMPLE_INPUT);
+    assert_eq!(part_1_generic::<4>(&input), 10605);
+    assert_eq!(part_2_generic::<4>(&input), 2713310158);
+}
@@ -1,12 +1,7 @@
 use std::{cmp::Ordering, collections::BinaryHeap};
 
-fn directions<const WIDTH: usize>() -> impl Iterator<Item = isize> {
-    use std::iter::once;
-    once(1isize)
-        .chain(once(-1))
-        .chain(once(WIDTH as isize))
-        .chain(once(-(WIDTH as isize)))
-}
+use crate::solution::Solution;
+use crate::util::{normalize_input, Grid, ParseError, ParseErrorKind};
 
 #[derive(Eq, PartialEq, Copy, Clone)]
 struct HeapEntry {
@@ -32,13 +27,13 @@ fn is_walk_possible(from: u8, to: u8) -> bool {
     (from).wrapping_sub(to).wrapping_add(1) < 27
 }
 
-fn breadth_first_search<const WIDTH: usize, const TOTAL_SIZE: usize>(
-    s: &str,
+/// The length of the shortest path from any of the starting positions to the
+/// goal, if there is one.
+pub fn breadth_first_search(
+    grid: Grid<u8>,
     starting_positions: impl Iterator<Item = usize>,
-) -> usize {
-    let grid = s.as_bytes();
-    assert_eq!(grid.len(), TOTAL_SIZE);
-    let mut visited = [false; TOTAL_SIZE];
+) -> Option<usize> {
+    let mut visited = vec![false; grid.cells().len()];
     let mut heap: BinaryHeap<_> = starting_positions
         .map(|pos| HeapEntry {
             pos,
@@ -51,43 +46,158 @@ fn breadth_first_search<const WIDTH: usize, const TOTAL_SIZE: usize>(
             continue;
         }
         visited[pos] = true;
-        for direction in directions::<WIDTH>() {
-            // We don't have to worry about left and right edges as we will
-            // reach '\n' first. The top edge will wrap around and the bottom
-            // edge lays outside of the array.
-            let new_pos = pos.wrapping_add(direction as usize);
-            if let Some(&to_value) = grid.get(new_pos) {
-                if !visited[new_pos] {
-                    if is_walk_possible(value, to_value) {
-                        heap.push(HeapEntry {
-                            pos: new_pos,
-                            score: score + 1,
-                            value: to_value,
-                        })
-                    } else if to_value == b'E' && value >= b'y' {
-                        return score + 1;
-                    }
+        for new_pos in grid.neighbours(pos) {
+            if !visited[new_pos] {
+                let to_value = grid[new_pos];
+                if is_walk_possible(value, to_value) {
+                    heap.push(HeapEntry {
+                        pos: new_pos,
+                        score: score + 1,
+                        value: to_value,
+                    })
+                } else if to_value == b'E' && value >= b'y' {
+                    return Some(score + 1);
                 }
             }
         }
     }
-    unreachable!()
+    None
+}
+
+/// The heights, with the start position.
+#[derive(Copy, Clone, Debug)]
+pub struct Heightmap<'a> {
+    pub grid: Grid<'a, u8>,
+    pub start: usize,
 }
 
-pub fn part_1_generic<const WIDTH: usize, const TOTAL_SIZE: usize>(s: &str) -> usize {
-    breadth_first_search::<WIDTH, TOTAL_SIZE>(s, memchr::memchr(b'S', s.as_bytes()).iter().copied())
+impl Heightmap<'_> {
+    fn shortest_path_from_start(&self) -> Option<usize> {
+        breadth_first_search(self.grid, std::iter::once(self.start))
+    }
+
+    fn shortest_path_from_lowest(&self) -> Option<usize> {
+        breadth_first_search(
+            self.grid,
+            memchr::memchr2_iter(b'S', b'a', self.grid.cells()),
+        )
+    }
+}
+
+pub fn parse(s: &str) -> Heightmap<'_> {
+    Heightmap {
+        grid: Grid::from_input(s),
+        start: memchr::memchr(b'S', s.as_bytes()).expect("no start position"),
+    }
 }
 
-pub fn part_2_generic<const WIDTH: usize, const TOTAL_SIZE: usize>(s: &str) -> usize {
-    breadth_first_search::<WIDTH, TOTAL_SIZE>(s, memchr::memchr2_iter(b'S', b'a', s.as_bytes()))
+pub fn solve_part_1(heightmap: &Heightmap) -> usize {
+    heightmap
+        .shortest_path_from_start()
+        .expect("no path to the goal")
+}
+
+pub fn solve_part_2(heightmap: &Heightmap) -> usize {
+    heightmap
+        .shortest_path_from_lowest()
+        .expect("no path to the goal")
 }
 
 pub fn part_1(s: &str) -> usize {
-    part_1_generic::<162, 6641>(s)
+    let s = &normalize_input(s);
+    solve_part_1(&parse(s))
 }
 
 pub fn part_2(s: &str) -> usize {
-    part_2_generic::<162, 6641>(s)
+    let s = &normalize_input(s);
+    solve_part_2(&parse(s))
+}
+
+/// Parse the heightmap, checking that it is rectangular, only contains
+/// heights and has exactly one start and one goal.
+fn try_heightmap(s: &str) -> Result<Heightmap<'_>, ParseError> {
+    let grid = Grid::try_from_input(s)?;
+    let (mut start, mut goal) = (None, None);
+    for (offset, &b) in grid.cells().iter().enumerate() {
+        let marker = match b {
+            b'a'..=b'z' | b'\n' => continue,
+            b'S' => &mut start,
+            b'E' => &mut goal,
+            _ => {
+                return Err(ParseError::at(
+                    s,
+                    offset,
+                    ParseErrorKind::Expected {
+                        what: "height, S or E",
+                        found: Some(b),
+                    },
+                ))
+            }
+        };
+        if marker.replace(offset).is_some() {
+            return Err(ParseError::at(
+                s,
+                offset,
+                ParseErrorKind::Invalid(if b == b'S' {
+                    "second start position"
+                } else {
+                    "second goal"
+                }),
+            ));
+        }
+    }
+    let end = grid.cells().len();
+    let start = start
+        .ok_or_else(|| ParseError::at(s, end, ParseErrorKind::Invalid("no start position")))?;
+    goal.ok_or_else(|| ParseError::at(s, end, ParseErrorKind::Invalid("no goal")))?;
+    Ok(Heightmap { grid, start })
+}
+
+fn no_path(s: &str) -> ParseError {
+    ParseError::at(s, 0, ParseErrorKind::Invalid("no path to the goal"))
+}
+
+pub fn try_part_1(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    try_heightmap(s)?
+        .shortest_path_from_start()
+        .ok_or_else(|| no_path(s))
+}
+
+pub fn try_part_2(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    try_heightmap(s)?
+        .shortest_path_from_lowest()
+        .ok_or_else(|| no_path(s))
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    type Parsed<'a> = Heightmap<'a>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Heightmap<'_> {
+        parse(input)
+    }
+
+    fn part_1(heightmap: &Heightmap<'_>) -> usize {
+        solve_part_1(heightmap)
+    }
+
+    fn part_2(heightmap: &Heightmap<'_>) -> usize {
+        solve_part_2(heightmap)
+    }
+
+    fn try_part_1(input: &str) -> Result<usize, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<usize, ParseError> {
+        try_part_2(input)
+    }
 }
 
 #[test]
@@ -112,7 +222,7 @@ abcryxxl
 accszExk
 acctuvwj
 abdefghi";
-    assert_eq!(part_1_generic::<9, 44>(input), 31);
+    assert_eq!(part_1(input), 31);
 }
 
 #[test]
@@ -123,7 +233,7 @@ abcryxxl
 accszExk
 acctuvwj
 abdefghi";
-    assert_eq!(part_2_generic::<9, 44>(input), 29);
+    assert_eq!(part_2(input), 29);
 }
 
 #[test]
@@ -137,3 +247,84 @@ fn test_part_2() {
     let input = include_str!("../input/2022/day12.txt");
     assert_eq!(part_2(input), 480);
 }
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(12);
+}
+
+#[test]
+fn test_trailing_newline() {
+    let input = "\
+Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi
+";
+    assert_eq!(part_1(input), 31);
+    assert_eq!(part_2(input), 29);
+}
+
+#[test]
+fn test_other_width() {
+    let input = "\
+Sbcdefghijklmnopqrstuvwxyz
+zzzzzzzzzzzzzzzzzzzzzzzzzE";
+    assert_eq!(part_1(input), 26);
+    assert_eq!(part_2(input), 26);
+}
+
+#[test]
+#[should_panic(expected = "row 2 has length 7, but the first row has length 8")]
+fn test_ragged_rows() {
+    let input = "\
+Sabqponm
+abcryxx
+accszExk";
+    part_1(input);
+}
+
+#[test]
+fn test_try_parts() {
+    let input = include_str!("../input/2022/day12.txt");
+    assert_eq!(try_part_1(input), Ok(part_1(input)));
+    assert_eq!(try_part_2(input), Ok(part_2(input)));
+    let error = |input: &str| try_part_1(input).unwrap_err().to_string();
+    assert_eq!(
+        error("Sab\nabc\nab!\nabE"),
+        "line 3, column 3: expected height, S or E, found '!'"
+    );
+    assert_eq!(
+        error("Sab\nabS\nabE"),
+        "line 2, column 3: second start position"
+    );
+    assert_eq!(
+        error("aab\nabc\nabE\n"),
+        "line 3, column 4: no start position"
+    );
+    assert_eq!(error("Sab\nabc"), "line 2, column 4: no goal");
+    assert_eq!(
+        error("Sab\nabc\nazE"),
+        "line 1, column 1: no path to the goal"
+    );
+    assert_eq!(
+        error("Sab\nabc\nab\n"),
+        "line 3, column 3: expected grid cell, found end of input"
+    );
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy(
+        "\
+Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi",
+    );
+    assert_eq!(part_1(&input), 31);
+    assert_eq!(part_2(&input), 29);
+    assert_eq!(try_part_1(&input), Ok(31));
+}
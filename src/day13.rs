@@ -1,157 +1,412 @@
-use std::{cmp::Ordering, num::NonZeroU8};
+use std::cmp::Ordering;
+use std::fmt::Display;
 
-fn compare(a: &[u8], b: &[u8]) -> Ordering {
-    let mut it_a = a.iter().copied().peekable();
-    let mut it_b = b.iter().copied().peekable();
-    let mut bracket_difference: i8 = 0;
-    let mut first_of_list = true;
-    loop {
-        dbg!((it_a.peek().copied().map(char::from), it_b.peek().copied().map(char::from)));
-        match (it_a.peek().copied(), it_b.peek().copied()) {
-            (Some(b']'), Some(b']')) => {
-                it_a.next();
-                it_b.next();
-            }
-            (Some(b']'), _) => {
-                // Close in first, not in second. This is allowed as long as the first was opened more.
-                if bracket_difference >= 0 {
-                    return Ordering::Less;
-                }
-                it_a.next();
-                bracket_difference += 1;
+use crate::solution::Solution;
+use crate::util::{normalize_input, ParseError, Scanner};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Token<'a> {
+    Open,
+    Close,
+    /// The digits of an integer.
+    Integer(&'a [u8]),
+}
+
+/// A stream of tokens of a single packet. An integer can be wrapped in any
+/// number of virtual lists, whose closing brackets are emitted right after the
+/// integer itself.
+struct TokenStream<'a> {
+    bytes: &'a [u8],
+    /// Virtual lists around the next integer.
+    wrapping: usize,
+    /// Virtual closing brackets still to be emitted.
+    closing: usize,
+}
+
+impl<'a> From<&'a [u8]> for TokenStream<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        TokenStream {
+            bytes,
+            wrapping: 0,
+            closing: 0,
+        }
+    }
+}
+
+impl<'a> TokenStream<'a> {
+    fn peek(&mut self) -> Option<Token<'a>> {
+        if self.closing > 0 {
+            return Some(Token::Close);
+        }
+        // Commas carry no information, since the brackets determine the
+        // structure. Any other unexpected bytes are skipped as well.
+        while let [b, rest @ ..] = self.bytes {
+            if matches!(b, b'[' | b']') || b.is_ascii_digit() {
+                break;
             }
-            (_, Some(b']')) => {
-                if bracket_difference <= 0 {
-                    return Ordering::Greater;
-                }
-                it_b.next();
-                bracket_difference -= 1;
+            self.bytes = rest;
+        }
+        match self.bytes {
+            [] => None,
+            [b'[', ..] => Some(Token::Open),
+            [b']', ..] => Some(Token::Close),
+            digits => {
+                let len = digits.iter().take_while(|b| b.is_ascii_digit()).count();
+                Some(Token::Integer(&digits[..len]))
             }
-            (Some(b'['), Some(b'[')) => {
-                it_a.next();
-                it_b.next();
-                first_of_list = true;
+        }
+    }
+
+    /// Skip the token that was last peeked.
+    fn advance(&mut self, token: Token<'a>) {
+        if self.closing > 0 {
+            self.closing -= 1;
+        } else if let Token::Integer(digits) = token {
+            self.bytes = &self.bytes[digits.len()..];
+            self.closing = std::mem::take(&mut self.wrapping);
+        } else {
+            self.bytes = &self.bytes[1..];
+        }
+    }
+
+    /// Treat the next integer as if it was surrounded by another list.
+    fn wrap(&mut self) {
+        self.wrapping += 1;
+    }
+}
+
+/// Compare two integers of any length, given as their decimal digits.
+fn compare_integers(a: &[u8], b: &[u8]) -> Ordering {
+    fn strip_leading_zeros(digits: &[u8]) -> &[u8] {
+        &digits[digits.iter().take_while(|d| **d == b'0').count()..]
+    }
+    let a = strip_leading_zeros(a);
+    let b = strip_leading_zeros(b);
+    // Without leading zeros, a longer number is always larger.
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+/// Compare two packets without allocating. Bytes other than brackets and
+/// digits are ignored, and a packet that ends early is considered smaller, so
+/// malformed input never causes a panic.
+pub fn compare(a: &[u8], b: &[u8]) -> Ordering {
+    let mut a = TokenStream::from(a);
+    let mut b = TokenStream::from(b);
+    loop {
+        match (a.peek(), b.peek()) {
+            (None, None) => return Ordering::Equal,
+            (Some(token_a @ Token::Open), Some(token_b @ Token::Open))
+            | (Some(token_a @ Token::Close), Some(token_b @ Token::Close)) => {
+                a.advance(token_a);
+                b.advance(token_b);
             }
-            (Some(b','), Some(b',')) => {
-                it_a.next();
-                it_b.next();
-                first_of_list = false;
+            // The first list ran out of items
+            (Some(Token::Close) | None, _) => return Ordering::Less,
+            (_, Some(Token::Close) | None) => return Ordering::Greater,
+            (Some(Token::Integer(_)), Some(token_b @ Token::Open)) => {
+                a.wrap();
+                b.advance(token_b);
             }
-            (Some(b'['), _) => {
-                it_a.next();
-                bracket_difference -= 1;
-                first_of_list = true;
+            (Some(token_a @ Token::Open), Some(Token::Integer(_))) => {
+                a.advance(token_a);
+                b.wrap();
             }
-            (_, Some(b'[')) => {
-                it_b.next();
-                bracket_difference += 1;
-                first_of_list = true;
+            (Some(token_a @ Token::Integer(x)), Some(token_b @ Token::Integer(y))) => {
+                match compare_integers(x, y) {
+                    Ordering::Equal => {
+                        a.advance(token_a);
+                        b.advance(token_b);
+                    }
+                    order => return order,
+                }
             }
-            (Some(next_a), Some(next_b)) => {
-                debug_assert!(next_a.is_ascii_digit());
-                debug_assert!(next_b.is_ascii_digit());
-
-                // If we are reading two numbers, but we were emulating a list
-                // and this is not the first number in it, the list is closed
-                // now, meaning that the side that emulated the list is
-                // smaller.
-                if !first_of_list && bracket_difference != 0 {
-                    return 0.cmp(&bracket_difference);
+        }
+    }
+}
+
+pub fn packet_pairs(s: &str) -> impl Iterator<Item = (&[u8], &[u8])> + '_ {
+    s.split("\n\n").map(|pair| {
+        let (a, b) = pair.split_once('\n').unwrap();
+        (a.as_bytes(), b.trim_end_matches('\n').as_bytes())
+    })
+}
+
+pub type PacketPair<'a> = (&'a [u8], &'a [u8]);
+
+pub fn parse(s: &str) -> Vec<PacketPair<'_>> {
+    packet_pairs(s).collect()
+}
+
+pub fn solve_part_1(pairs: &[PacketPair]) -> usize {
+    pairs
+        .iter()
+        .enumerate()
+        .filter(|(_, (a, b))| compare(a, b) == Ordering::Less)
+        .map(|(index, _)| index + 1)
+        .sum()
+}
+
+pub fn solve_part_2(pairs: &[PacketPair]) -> usize {
+    const DIVIDER_1: &[u8] = b"[[2]]";
+    const DIVIDER_2: &[u8] = b"[[6]]";
+    // There is no need to sort, we only need to know how many packets come
+    // before either divider.
+    let (mut before_1, mut before_2) = (1, 2);
+    for packet in pairs.iter().flat_map(|&(a, b)| [a, b]) {
+        if compare(packet, DIVIDER_1) == Ordering::Less {
+            before_1 += 1;
+            before_2 += 1;
+        } else if compare(packet, DIVIDER_2) == Ordering::Less {
+            before_2 += 1;
+        }
+    }
+    before_1 * before_2
+}
+
+pub fn part_1(s: &str) -> usize {
+    let s = &normalize_input(s);
+    solve_part_1(&parse(s))
+}
+
+pub fn part_2(s: &str) -> usize {
+    let s = &normalize_input(s);
+    solve_part_2(&parse(s))
+}
+
+/// Read a single packet, checking that it is a well-formed list.
+fn try_packet<'a>(scanner: &mut Scanner<'a>) -> Result<&'a [u8], ParseError> {
+    let packet = scanner.rest();
+    let start = scanner.position();
+    if scanner.peek() != Some(b'[') {
+        return Err(scanner.expected("list"));
+    }
+    let mut depth = 0;
+    loop {
+        // A value, or the end of a list that was just opened.
+        match scanner.peek() {
+            Some(b'[') => {
+                scanner.tag("[")?;
+                depth += 1;
+                if scanner.peek() != Some(b']') {
+                    continue;
                 }
-                // We know the first digits, now read the rest
-                it_a.next();
-                it_b.next();
-                match (it_a.peek().unwrap(), it_b.peek().unwrap()) {
-                    (b'0', b'0') => {
-                        it_a.next();
-                        it_b.next();
-                    } // Both 10
-                    (b'0', _) => return Ordering::Greater,
-                    (_, b'0') => return Ordering::Less,
-                    (_, _) => {
-                        if let order @ (Ordering::Greater | Ordering::Less) = next_a.cmp(&next_b) {
-                            return order;
-                        }
+            }
+            Some(b) if b.is_ascii_digit() => {
+                scanner.take_while(|b| b.is_ascii_digit());
+            }
+            _ => return Err(scanner.expected("integer or list")),
+        }
+        // Close any number of lists, then either a separator or the end.
+        loop {
+            match scanner.peek() {
+                Some(b']') => {
+                    scanner.tag("]")?;
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(&packet[..scanner.position() - start]);
                     }
                 }
-            }
-            (None, a) | (a, None) => {
-                // If both lists end, they must have been equal, which doesn't happen.
-                // If one lists ends and the other doesn't, that means that we
-                // should have labeled the shorter list as `Less` already.
-                unreachable!()
+                Some(b',') => {
+                    scanner.tag(",")?;
+                    break;
+                }
+                _ => return Err(scanner.expected("',' or ']'")),
             }
         }
     }
 }
 
-/// Map b"10" to a single-byte: b'9' + 1.
-fn integer_combiner<'a>(it: &'a mut impl Iterator<Item = u8>) -> impl Iterator<Item = u8> + 'a {
-    std::iter::from_fn(move || {
-        it.next()
-            .map(|d| if d == b'1' {
-                it.next()
-                    .map(|d2| if d2 == b'0' {
-                        b'9' + 1
-                    } else {
-                        d2
-                    })
-                    .unwrap()
-            } else {
-                d
-            })
-    })
+pub fn try_packet_pairs(s: &str) -> Result<Vec<PacketPair<'_>>, ParseError> {
+    let mut scanner = Scanner::new(s);
+    let mut pairs = Vec::new();
+    loop {
+        let a = try_packet(&mut scanner)?;
+        scanner.tag("\n")?;
+        let b = try_packet(&mut scanner)?;
+        scanner.line_end()?;
+        pairs.push((a, b));
+        if scanner.is_at_end() {
+            return Ok(pairs);
+        }
+        scanner.tag("\n")?;
+    }
+}
+
+pub fn try_part_1(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    Ok(solve_part_1(&try_packet_pairs(s)?))
+}
+
+pub fn try_part_2(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    Ok(solve_part_2(&try_packet_pairs(s)?))
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+    type Parsed<'a> = Vec<PacketPair<'a>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Vec<PacketPair<'_>> {
+        parse(input)
+    }
+
+    fn part_1(pairs: &Vec<PacketPair<'_>>) -> usize {
+        solve_part_1(pairs)
+    }
+
+    fn part_2(pairs: &Vec<PacketPair<'_>>) -> usize {
+        solve_part_2(pairs)
+    }
+
+    fn try_part_1(input: &str) -> Result<usize, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<usize, ParseError> {
+        try_part_2(input)
+    }
 }
 
-struct BracketEmulator<I: Iterator<Item = u8>> {
-    brackets: u8,
-    bracket_current: u8,
-    first_integer: Option<NonZeroU8>,
-    it: I
+/// The digits of an integer in a packet, as written. There is at least one
+/// digit, and leading zeros are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Integer(String);
+
+impl Integer {
+    /// Parse an integer, which must make up the whole input.
+    pub fn parse(s: &str) -> Result<Integer, ParseError> {
+        let mut scanner = Scanner::new(s);
+        if !scanner.peek().is_some_and(|b| b.is_ascii_digit()) {
+            return Err(scanner.expected("integer"));
+        }
+        scanner.take_while(|b| b.is_ascii_digit());
+        if !scanner.is_at_end() {
+            return Err(scanner.expected("end of input"));
+        }
+        Ok(Integer(s.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The order of the puzzle, by value rather than by digits.
+    pub fn order(&self, other: &Integer) -> Ordering {
+        compare_integers(self.0.as_bytes(), other.0.as_bytes())
+    }
 }
 
-impl<I: Iterator<Item = u8>> From<I> for BracketEmulator<I> {
-    fn from(it: I) -> Self {
-        BracketEmulator { brackets: 0, bracket_current: 0, first_integer: None, it }
+impl Display for Integer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
     }
 }
 
-impl<I: Iterator<Item = u8>> BracketEmulator<I> {
-    fn surround_first_integer_with_brackets(&mut self, integer: NonZeroU8) {
-        self.first_integer = Some(integer);
-        self.brackets += 1;
+/// An owned packet tree. Integers keep their digits as written, so they can
+/// be of any size and printing a packet gives back the original text.
+///
+/// Packets are equal when they are written the same way. The order of the
+/// puzzle is [`Packet::order`] instead of `Ord`, as it puts `[[2]]` level
+/// with `[2]` and `[007]` level with `[7]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
+    Integer(Integer),
+    List(Vec<Packet>),
+}
+
+impl Packet {
+    /// Parse a single packet, which must make up the whole input.
+    pub fn parse(s: &str) -> Result<Packet, ParseError> {
+        let mut scanner = Scanner::new(s);
+        let packet = try_packet(&mut scanner)?;
+        if !scanner.is_at_end() {
+            return Err(scanner.expected("end of input"));
+        }
+        Ok(Packet::from_checked(packet))
+    }
+
+    /// Build the tree of a packet that was already checked by `try_packet`.
+    /// This uses an explicit stack, so deeply nested packets are fine.
+    fn from_checked(mut bytes: &[u8]) -> Packet {
+        let mut lists: Vec<Vec<Packet>> = Vec::new();
+        loop {
+            match bytes {
+                [b'[', rest @ ..] => {
+                    lists.push(Vec::new());
+                    bytes = rest;
+                }
+                [b']', rest @ ..] => {
+                    let list = Packet::List(lists.pop().unwrap());
+                    match lists.last_mut() {
+                        Some(parent) => parent.push(list),
+                        None => return list,
+                    }
+                    bytes = rest;
+                }
+                [b',', rest @ ..] => bytes = rest,
+                digits => {
+                    let len = digits.iter().take_while(|b| b.is_ascii_digit()).count();
+                    let integer = String::from_utf8(digits[..len].to_vec()).unwrap();
+                    lists
+                        .last_mut()
+                        .unwrap()
+                        .push(Packet::Integer(Integer(integer)));
+                    bytes = &digits[len..];
+                }
+            }
+        }
     }
 
-    fn peek(&mut self) -> Option<>
+    /// The order of the puzzle, which `compare` gives for packets as text.
+    pub fn order(&self, other: &Packet) -> Ordering {
+        match (self, other) {
+            (Packet::Integer(a), Packet::Integer(b)) => a.order(b),
+            (Packet::List(a), Packet::List(b)) => order_lists(a, b),
+            (Packet::Integer(_), Packet::List(b)) => order_lists(std::slice::from_ref(self), b),
+            (Packet::List(a), Packet::Integer(_)) => order_lists(a, std::slice::from_ref(other)),
+        }
+    }
+}
+
+/// Lists are ordered by their first item that differs, and then by length.
+fn order_lists(a: &[Packet], b: &[Packet]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| a.order(b))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
 }
 
-impl<I: Iterator<Item = u8>> Iterator for BracketEmulator<I> {
-    type Item = u8;
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(first_integer) = self.first_integer {
-            if self.brackets != self.bracket_current {
-                debug_assert!(self.brackets > self.bracket_current);
-                self.bracket_current += 1;
-                Some(b'[')
-            } else {
-                self.brackets = 0;
-                self.first_integer.take().map(u8::from)
+impl Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Integer(integer) => write!(f, "{integer}"),
+            Packet::List(items) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
             }
-        } else if self.bracket_current != 0 {
-            self.bracket_current -= 1;
-            Some(b']')
-        } else {
-            self.it.next()
         }
     }
 }
 
-pub fn order(it1: impl Iterator<Item = u8>, it2: impl Iterator<Item = u8>) -> impl Iterator<Item = u8> {
-    let mut it1 = integer_combiner(&mut it1);
-    let mut it2 = integer_combiner(&mut it2);
-    let mut it1 = BracketEmulator::from(it1);
-    let mut it2 = BracketEmulator::from(it2);
-    for 
+/// All packets of the input in order, ready to be sorted by [`Packet::order`].
+pub fn try_packets(s: &str) -> Result<Vec<Packet>, ParseError> {
+    let s = &normalize_input(s);
+    Ok(try_packet_pairs(s)?
+        .into_iter()
+        .flat_map(|(a, b)| [a, b])
+        .map(Packet::from_checked)
+        .collect())
 }
 
 #[test]
@@ -161,6 +416,16 @@ fn test_number_order() {
     assert_eq!(compare(b"[8]", b"[7]"), Ordering::Greater);
     assert_eq!(compare(b"[10]", b"[1]"), Ordering::Greater);
     assert_eq!(compare(b"[10]", b"[9]"), Ordering::Greater);
+    assert_eq!(compare(b"[11]", b"[2]"), Ordering::Greater);
+    assert_eq!(compare(b"[19]", b"[110]"), Ordering::Less);
+    assert_eq!(compare(b"[007]", b"[7]"), Ordering::Equal);
+    assert_eq!(
+        compare(
+            b"[123456789012345678901234567890]",
+            b"[123456789012345678901234567891]"
+        ),
+        Ordering::Less
+    );
 }
 
 #[test]
@@ -176,6 +441,8 @@ fn test_nested_list() {
     assert_eq!(compare(b"[[[[5]]]]", b"[7]"), Ordering::Less);
     assert_eq!(compare(b"[[[[5,8]]]]", b"[7]"), Ordering::Less);
     assert_eq!(compare(b"[[1,2]]", b"[1,2]"), Ordering::Greater);
+    assert_eq!(compare(b"[[5,[6]]]", b"[5,7]"), Ordering::Greater);
+    assert_eq!(compare(b"[[5]]", b"[5,1]"), Ordering::Less);
 }
 
 #[test]
@@ -187,5 +454,300 @@ fn test_part_1_examples() {
     assert_eq!(compare(b"[7,7,7,7]", b"[7,7,7]"), Ordering::Greater);
     assert_eq!(compare(b"[]", b"[3]"), Ordering::Less);
     assert_eq!(compare(b"[[[]]]", b"[[]]"), Ordering::Greater);
-    assert_eq!(compare(b"[1,[2,[3,[4,[5,6,7]]]],8,9]", b"[1,[2,[3,[4,[5,6,0]]]],8,9]"), Ordering::Greater);
+    assert_eq!(
+        compare(
+            b"[1,[2,[3,[4,[5,6,7]]]],8,9]",
+            b"[1,[2,[3,[4,[5,6,0]]]],8,9]"
+        ),
+        Ordering::Greater
+    );
+}
+
+#[cfg(test)]
+const EXAMPLE_INPUT: &str = "\
+[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]
+";
+
+#[test]
+fn test_part_1_example() {
+    assert_eq!(part_1(EXAMPLE_INPUT), 13);
+}
+
+#[test]
+fn test_part_2_example() {
+    assert_eq!(part_2(EXAMPLE_INPUT), 140);
+}
+
+// Made with `aoc generate 13 150 --seed 2022`; there is no puzzle input here
+#[test]
+fn test_part_1_generated() {
+    let input = include_str!("../input/2022/day13/generated.txt");
+    assert_eq!(part_1(input), 4713);
+}
+
+#[test]
+fn test_part_2_generated() {
+    let input = include_str!("../input/2022/day13/generated.txt");
+    assert_eq!(part_2(input), 32045);
+}
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(13);
+}
+
+#[test]
+fn test_packet_display_round_trip() {
+    for line in EXAMPLE_INPUT
+        .split_terminator('\n')
+        .filter(|l| !l.is_empty())
+    {
+        assert_eq!(Packet::parse(line).unwrap().to_string(), line);
+    }
+    assert_eq!(
+        Packet::parse("[1234,[],[[567890]]]").unwrap().to_string(),
+        "[1234,[],[[567890]]]"
+    );
+}
+
+#[test]
+fn test_packet_large_integers() {
+    let big = "[1234567890123456789012345678901234567890]";
+    let bigger = "[1234567890123456789012345678901234567891]";
+    assert_eq!(Packet::parse(big).unwrap().to_string(), big);
+    let order = |a: &str, b: &str| Packet::parse(a).unwrap().order(&Packet::parse(b).unwrap());
+    assert_eq!(order(big, bigger), Ordering::Less);
+    assert_eq!(Packet::parse("[007]").unwrap().to_string(), "[007]");
+    assert_eq!(order("[007]", "[7]"), Ordering::Equal);
+    assert_ne!(
+        Packet::parse("[007]").unwrap(),
+        Packet::parse("[7]").unwrap()
+    );
+}
+
+#[test]
+fn test_integer_parse() {
+    assert_eq!(Integer::parse("007").unwrap().as_str(), "007");
+    assert_eq!(Integer::parse("0").unwrap().to_string(), "0");
+    let error = |input: &str| Integer::parse(input).unwrap_err().to_string();
+    assert_eq!(
+        error(""),
+        "line 1, column 1: expected integer, found end of input"
+    );
+    assert_eq!(
+        error("abc"),
+        "line 1, column 1: expected integer, found 'a'"
+    );
+    assert_eq!(
+        error("12a"),
+        "line 1, column 3: expected end of input, found 'a'"
+    );
+    assert_eq!(error("-1"), "line 1, column 1: expected integer, found '-'");
+}
+
+#[test]
+fn test_packet_parse_errors() {
+    let error = |input: &str| Packet::parse(input).unwrap_err().to_string();
+    assert_eq!(error("7"), "line 1, column 1: expected list, found '7'");
+    assert_eq!(
+        error("[1,]"),
+        "line 1, column 4: expected integer or list, found ']'"
+    );
+    assert_eq!(
+        error("[1]]"),
+        "line 1, column 4: expected end of input, found ']'"
+    );
+}
+
+#[test]
+fn test_packet_order() {
+    let order = |a: &str, b: &str| Packet::parse(a).unwrap().order(&Packet::parse(b).unwrap());
+    assert_eq!(order("[10]", "[9]"), Ordering::Greater);
+    assert_eq!(order("[123]", "[[45],1]"), Ordering::Greater);
+    assert_eq!(order("[[2]]", "[2]"), Ordering::Equal);
+    assert_ne!(
+        Packet::parse("[[2]]").unwrap(),
+        Packet::parse("[2]").unwrap()
+    );
+}
+
+#[test]
+fn test_packet_order_matches_compare() {
+    let lines: Vec<_> = EXAMPLE_INPUT
+        .split_terminator('\n')
+        .filter(|l| !l.is_empty())
+        .collect();
+    for a in &lines {
+        for b in &lines {
+            assert_eq!(
+                Packet::parse(a).unwrap().order(&Packet::parse(b).unwrap()),
+                compare(a.as_bytes(), b.as_bytes()),
+                "comparing {a} and {b}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_packet_sort_example() {
+    let dividers = [
+        Packet::parse("[[2]]").unwrap(),
+        Packet::parse("[[6]]").unwrap(),
+    ];
+    let mut packets = try_packets(EXAMPLE_INPUT).unwrap();
+    packets.extend(dividers.iter().cloned());
+    packets.sort_by(Packet::order);
+    let decoder_key: usize = packets
+        .iter()
+        .enumerate()
+        .filter(|(_, packet)| dividers.contains(packet))
+        .map(|(index, _)| index + 1)
+        .product();
+    assert_eq!(decoder_key, 140);
+}
+
+#[test]
+fn test_malformed_input() {
+    let inputs: [&[u8]; 8] = [b"", b"[", b"]", b"[[", b"]]", b"[1,", b"abc", b"[1]]]"];
+    for a in inputs {
+        for b in inputs {
+            compare(a, b);
+        }
+    }
+    assert_eq!(compare(b"[1", b"[1]"), Ordering::Less);
+    assert_eq!(compare(b"[1,2", b"[1]"), Ordering::Greater);
+}
+
+#[cfg(test)]
+use crate::generate::Rng;
+
+#[cfg(test)]
+fn random_integer(rng: &mut Rng) -> u128 {
+    if rng.below(2) == 0 {
+        rng.below(11).into()
+    } else {
+        let integer = (u128::from(rng.next_u64()) << 64) | u128::from(rng.next_u64());
+        integer >> rng.below(128)
+    }
+}
+
+#[cfg(test)]
+fn random_packet(rng: &mut Rng, depth: usize) -> Packet {
+    if depth == 0 || rng.below(3) == 0 {
+        Packet::Integer(Integer::parse(&random_integer(rng).to_string()).unwrap())
+    } else {
+        let len = rng.below(5) as usize;
+        Packet::List((0..len).map(|_| random_packet(rng, depth - 1)).collect())
+    }
+}
+
+/// Make small changes to a packet, so that comparisons often get far into
+/// both packets.
+#[cfg(test)]
+fn perturb(rng: &mut Rng, packet: &Packet) -> Packet {
+    match packet {
+        Packet::Integer(integer) => match rng.below(6) {
+            0 | 1 => Packet::List(vec![perturb(rng, packet)]),
+            2 => Packet::Integer(Integer::parse(&random_integer(rng).to_string()).unwrap()),
+            _ => Packet::Integer(integer.clone()),
+        },
+        Packet::List(items) => {
+            let mut items: Vec<_> = items.iter().map(|item| perturb(rng, item)).collect();
+            match rng.below(8) {
+                0 => {
+                    items.pop();
+                }
+                1 => items.push(random_packet(rng, 2)),
+                _ => {}
+            }
+            Packet::List(items)
+        }
+    }
+}
+
+#[test]
+fn test_compare_random_packets() {
+    let mut rng = Rng::new(0x2022_1213);
+    for _ in 0..10_000 {
+        let a = Packet::List(vec![random_packet(&mut rng, 6)]);
+        let b = if rng.below(2) == 0 {
+            perturb(&mut rng, &a)
+        } else {
+            Packet::List(vec![random_packet(&mut rng, 6)])
+        };
+        let (a_str, b_str) = (a.to_string(), b.to_string());
+        assert_eq!(
+            compare(a_str.as_bytes(), b_str.as_bytes()),
+            a.order(&b),
+            "comparing {a_str} and {b_str}"
+        );
+    }
+}
+
+#[test]
+fn test_compare_deep_nesting() {
+    let depth = 100_000;
+    let a = format!("{}1{}", "[".repeat(depth), "]".repeat(depth));
+    let b = format!("{}2{}", "[".repeat(depth), "]".repeat(depth));
+    assert_eq!(compare(a.as_bytes(), b"[1]"), Ordering::Equal);
+    assert_eq!(compare(a.as_bytes(), b.as_bytes()), Ordering::Less);
+    assert_eq!(compare(b"[2]", a.as_bytes()), Ordering::Greater);
+}
+
+#[test]
+fn test_try_parts() {
+    assert_eq!(try_part_1(EXAMPLE_INPUT), Ok(13));
+    assert_eq!(try_part_2(EXAMPLE_INPUT), Ok(140));
+    assert_eq!(try_part_1(EXAMPLE_INPUT.trim_end()), Ok(13));
+    let error = |input: &str| try_part_1(input).unwrap_err().to_string();
+    assert_eq!(
+        error("[1,[2,]]\n[1]"),
+        "line 1, column 7: expected integer or list, found ']'"
+    );
+    assert_eq!(
+        error("[1,[2]\n[1]"),
+        "line 1, column 7: expected ',' or ']', found '\\n'"
+    );
+    assert_eq!(
+        error("[1]\n[2]\n[3]\n[4]"),
+        "line 3, column 1: expected \"\\n\", found '['"
+    );
+    assert_eq!(
+        error("[1]\n[2]\n\n[3]"),
+        "line 4, column 4: expected \"\\n\", found end of input"
+    );
+    assert_eq!(
+        error("[[]]\r[]"),
+        "line 1, column 5: expected \"\\n\", found '\\r'"
+    );
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy(EXAMPLE_INPUT);
+    assert_eq!(part_1(&input), 13);
+    assert_eq!(part_2(&input), 140);
+    assert_eq!(try_part_1(&input), Ok(13));
 }
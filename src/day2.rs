@@ -1,8 +1,13 @@
+use std::fmt::{self, Display};
+
+use crate::solution::Solution;
+use crate::util::{normalize_input, ParseError, Scanner};
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-struct Play([u8; 2]);
+pub struct Play([u8; 2]);
 
 impl Play {
-    const fn points(self) -> u8 {
+    pub const fn points(self) -> u8 {
         const DIFF: u8 = b'A'
             .wrapping_sub(b'X')
             .wrapping_add(1)
@@ -24,7 +29,7 @@ impl Play {
         }
     }
 
-    const fn points_part_2(self) -> u8 {
+    pub const fn points_part_2(self) -> u8 {
         let [a, b] = self.0;
         // 0 for lose, 1 for draw, 2 for win
         let win_lose_draw = 0b0000_0011 & b;
@@ -47,16 +52,279 @@ impl Play {
     }
 }
 
-fn plays(input: &str) -> impl Iterator<Item = Play> + '_ {
+#[cfg(feature = "nightly")]
+pub fn plays(input: &str) -> impl Iterator<Item = Play> + '_ {
     input.bytes().step_by(2).array_chunks().map(Play)
 }
 
+#[cfg(not(feature = "nightly"))]
+pub fn plays(input: &str) -> impl Iterator<Item = Play> + '_ {
+    // Every line is "A X\n", except for the last which may lack the newline.
+    input
+        .as_bytes()
+        .chunks(4)
+        .map(|line| Play([line[0], line[2]]))
+}
+
+pub fn try_plays(input: &str) -> Result<Vec<Play>, ParseError> {
+    let mut scanner = Scanner::new(input);
+    let mut plays = Vec::new();
+    while !scanner.is_at_end() {
+        let opponent = scanner.one_of(b"ABC", "A, B or C")?;
+        scanner.tag(" ")?;
+        let response = scanner.one_of(b"XYZ", "X, Y or Z")?;
+        scanner.line_end()?;
+        plays.push(Play([opponent, response]));
+    }
+    Ok(plays)
+}
+
+/// How a round ends for the player.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+/// How to read the second column of a strategy guide.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// The shape to respond with, as in part 1.
+    Shape,
+    /// How the round should end, as in part 2.
+    Outcome,
+}
+
+/// A game with an odd number of shapes arranged in a circle, where every
+/// shape beats the half of the other shapes that come right before it. Rock,
+/// paper, scissors is the game with three shapes. Rock, paper, scissors,
+/// lizard, Spock is the game with five, in the order rock, Spock, paper,
+/// lizard, scissors.
+///
+/// In a strategy guide, the shapes of the opponent are the first letters of
+/// the alphabet, and the shapes to respond with the last ones. Outcomes are
+/// always `X`, `Y` and `Z`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    shape_scores: Vec<usize>,
+    outcome_scores: [usize; 3],
+}
+
+const ALPHABET: &[u8; 26] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// A number of shapes that no game can be played with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameError {
+    /// With an even number of shapes, some pairs of shapes would tie.
+    EvenShapes(usize),
+    /// There are not enough letters to write down more shapes.
+    TooManyShapes(usize),
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::EvenShapes(shapes) => {
+                write!(f, "a game needs an odd number of shapes, not {shapes}")
+            }
+            GameError::TooManyShapes(shapes) => {
+                write!(f, "a game can have at most 25 shapes, not {shapes}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+impl Game {
+    pub fn new(shape_scores: Vec<usize>, outcome_scores: [usize; 3]) -> Result<Self, GameError> {
+        let shapes = shape_scores.len();
+        if shapes > ALPHABET.len() {
+            Err(GameError::TooManyShapes(shapes))
+        } else if shapes % 2 == 1 {
+            Ok(Game {
+                shape_scores,
+                outcome_scores,
+            })
+        } else {
+            Err(GameError::EvenShapes(shapes))
+        }
+    }
+
+    /// A game with `shapes` shapes worth 1, 2, 3 and so on, where losing is
+    /// worth 0, a draw 3 and winning 6.
+    pub fn cyclic(shapes: usize) -> Result<Self, GameError> {
+        Game::new((1..=shapes).collect(), [0, 3, 6])
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Game::cyclic(3).expect("three shapes make a game")
+    }
+
+    /// The score for responding with each shape.
+    pub fn shape_scores(&self) -> &[usize] {
+        &self.shape_scores
+    }
+
+    /// The scores for losing, drawing and winning.
+    pub fn outcome_scores(&self) -> [usize; 3] {
+        self.outcome_scores
+    }
+
+    /// Whether this is the game of the puzzle, with its usual scores.
+    fn is_rock_paper_scissors(&self) -> bool {
+        self.shape_scores == [1, 2, 3] && self.outcome_scores == [0, 3, 6]
+    }
+
+    pub fn shapes(&self) -> usize {
+        self.shape_scores.len()
+    }
+
+    /// How a round ends when responding to `opponent` with `response`.
+    pub fn outcome(&self, opponent: usize, response: usize) -> Outcome {
+        match (response + self.shapes() - opponent) % self.shapes() {
+            0 => Outcome::Draw,
+            steps if steps <= self.shapes() / 2 => Outcome::Win,
+            _ => Outcome::Lose,
+        }
+    }
+
+    pub fn score(&self, opponent: usize, response: usize) -> usize {
+        let outcome = self.outcome(opponent, response);
+        self.shape_scores[response] + self.outcome_scores[outcome as usize]
+    }
+
+    /// The response to `opponent` that ends the round with `outcome`. When
+    /// several shapes do, the one with the highest score is picked, and the
+    /// closest one of those.
+    pub fn response(&self, opponent: usize, outcome: Outcome) -> usize {
+        let shapes = self.shapes();
+        let direction = match outcome {
+            Outcome::Draw => return opponent,
+            Outcome::Win => 1,
+            Outcome::Lose => shapes - 1,
+        };
+        (1..=shapes / 2)
+            .map(|steps| (opponent + steps * direction) % shapes)
+            .rev()
+            .max_by_key(|&response| self.shape_scores[response])
+            .unwrap()
+    }
+
+    /// The total score of a strategy guide, reading the second column as
+    /// `column`.
+    pub fn try_score(&self, input: &str, column: Column) -> Result<usize, ParseError> {
+        if self.is_rock_paper_scissors() {
+            return match column {
+                Column::Shape => try_part_1(input),
+                Column::Outcome => try_part_2(input),
+            };
+        }
+        self.try_score_by_rules(input, column)
+    }
+
+    /// Like `try_score`, but always working out every round from the rules.
+    fn try_score_by_rules(&self, input: &str, column: Column) -> Result<usize, ParseError> {
+        let input = &normalize_input(input);
+        let opponents = &ALPHABET[..self.shapes()];
+        let responses = &ALPHABET[ALPHABET.len() - self.shapes()..];
+        let mut scanner = Scanner::new(input);
+        let mut score = 0;
+        while !scanner.is_at_end() {
+            let opponent = scanner.one_of(opponents, "a shape")? - b'A';
+            let opponent = usize::from(opponent);
+            scanner.tag(" ")?;
+            let response = match column {
+                Column::Shape => {
+                    let response = scanner.one_of(responses, "a shape")?;
+                    usize::from(response - responses[0])
+                }
+                Column::Outcome => {
+                    let outcome = match scanner.one_of(b"XYZ", "X, Y or Z")? {
+                        b'X' => Outcome::Lose,
+                        b'Y' => Outcome::Draw,
+                        _ => Outcome::Win,
+                    };
+                    self.response(opponent, outcome)
+                }
+            };
+            scanner.line_end()?;
+            score += self.score(opponent, response);
+        }
+        Ok(score)
+    }
+}
+
+pub fn parse(input: &str) -> Vec<Play> {
+    plays(input).collect()
+}
+
+pub fn solve_part_1(plays: &[Play]) -> usize {
+    plays
+        .iter()
+        .copied()
+        .map(Play::points)
+        .map(usize::from)
+        .sum()
+}
+
+pub fn solve_part_2(plays: &[Play]) -> usize {
+    plays
+        .iter()
+        .copied()
+        .map(Play::points_part_2)
+        .map(usize::from)
+        .sum()
+}
+
 pub fn part_1(input: &str) -> usize {
-    plays(input).map(Play::points).map(usize::from).sum()
+    let input = &normalize_input(input);
+    solve_part_1(&parse(input))
 }
 
 pub fn part_2(input: &str) -> usize {
-    plays(input).map(Play::points_part_2).map(usize::from).sum()
+    let input = &normalize_input(input);
+    solve_part_2(&parse(input))
+}
+
+pub fn try_part_1(input: &str) -> Result<usize, ParseError> {
+    let input = &normalize_input(input);
+    Ok(solve_part_1(&try_plays(input)?))
+}
+
+pub fn try_part_2(input: &str) -> Result<usize, ParseError> {
+    let input = &normalize_input(input);
+    Ok(solve_part_2(&try_plays(input)?))
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    type Parsed<'a> = Vec<Play>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Vec<Play> {
+        parse(input)
+    }
+
+    fn part_1(plays: &Vec<Play>) -> usize {
+        solve_part_1(plays)
+    }
+
+    fn part_2(plays: &Vec<Play>) -> usize {
+        solve_part_2(plays)
+    }
+
+    fn try_part_1(input: &str) -> Result<usize, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<usize, ParseError> {
+        try_part_2(input)
+    }
 }
 
 #[test]
@@ -114,3 +382,135 @@ fn test_part_2() {
     let input = include_str!("../input/2022/day2.txt");
     assert_eq!(part_2(input), 11657);
 }
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(2);
+}
+
+#[test]
+fn test_try_parts() {
+    let input = include_str!("../input/2022/day2.txt");
+    assert_eq!(try_part_1(input), Ok(part_1(input)));
+    assert_eq!(try_part_2(input), Ok(part_2(input)));
+    assert_eq!(
+        try_part_1("A Y\nB W\n").unwrap_err().to_string(),
+        "line 2, column 3: expected X, Y or Z, found 'W'"
+    );
+}
+
+#[test]
+fn test_game() {
+    // The rules agree with the fast path for rock, paper, scissors
+    let game = Game::rock_paper_scissors();
+    assert_eq!(Game::cyclic(3), Ok(game.clone()));
+    assert_eq!(game.shape_scores(), [1, 2, 3]);
+    assert_eq!(game.outcome_scores(), [0, 3, 6]);
+    for a in b'A'..=b'C' {
+        for b in b'X'..=b'Z' {
+            let play = Play([a, b]);
+            let line = format!("{} {}\n", char::from(a), char::from(b));
+            assert_eq!(
+                game.try_score_by_rules(&line, Column::Shape),
+                Ok(usize::from(play.points()))
+            );
+            assert_eq!(
+                game.try_score_by_rules(&line, Column::Outcome),
+                Ok(usize::from(play.points_part_2()))
+            );
+        }
+    }
+    let input = include_str!("../input/2022/day2.txt");
+    assert_eq!(game.try_score(input, Column::Shape), Ok(part_1(input)));
+    assert_eq!(game.try_score(input, Column::Outcome), Ok(part_2(input)));
+    assert_eq!(
+        game.try_score_by_rules(input, Column::Shape),
+        Ok(part_1(input))
+    );
+    assert_eq!(
+        game.try_score_by_rules(input, Column::Outcome),
+        Ok(part_2(input))
+    );
+
+    // Other scores go through the rules
+    let game = Game::new(vec![1, 2, 3], [0, 3, 7]).unwrap();
+    assert_eq!(
+        game.try_score("A Y\nC X\n", Column::Shape),
+        Ok(2 + 7 + 1 + 7)
+    );
+
+    // Rock, Spock, paper, lizard, scissors
+    let game = Game::cyclic(5).unwrap();
+    assert_eq!(game.outcome(0, 4), Outcome::Lose);
+    assert_eq!(game.outcome(3, 1), Outcome::Lose);
+    assert_eq!(game.outcome(1, 3), Outcome::Win);
+    assert_eq!(game.outcome(2, 2), Outcome::Draw);
+    assert_eq!(game.try_score("B X\n", Column::Shape), Ok(3 + 6));
+    assert_eq!(game.try_score("A Z\n", Column::Outcome), Ok(3 + 6));
+    assert_eq!(game.try_score("E X\n", Column::Outcome), Ok(4));
+    assert_eq!(
+        game.try_score("A Z\nF Z\n", Column::Shape)
+            .unwrap_err()
+            .to_string(),
+        "line 2, column 1: expected a shape, found 'F'"
+    );
+    assert_eq!(
+        game.try_score("A U\n", Column::Shape)
+            .unwrap_err()
+            .to_string(),
+        "line 1, column 3: expected a shape, found 'U'"
+    );
+    assert_eq!(
+        game.try_score("A V\n", Column::Outcome)
+            .unwrap_err()
+            .to_string(),
+        "line 1, column 3: expected X, Y or Z, found 'V'"
+    );
+
+    // Every shape beats half of the others, and loses to the other half
+    let game = Game::new(vec![5, 1, 7, 2, 4, 6, 3], [1, 2, 4]).unwrap();
+    for opponent in 0..7 {
+        let wins = (0..7)
+            .filter(|&response| game.outcome(opponent, response) == Outcome::Win)
+            .count();
+        assert_eq!(wins, 3);
+        for response in 0..7 {
+            let outcome = game.outcome(opponent, response);
+            let reverse = game.outcome(response, opponent);
+            assert_eq!(outcome as usize + reverse as usize, 2);
+        }
+        for outcome in [Outcome::Lose, Outcome::Draw, Outcome::Win] {
+            let response = game.response(opponent, outcome);
+            assert_eq!(game.outcome(opponent, response), outcome);
+            let best = (0..7)
+                .filter(|&other| game.outcome(opponent, other) == outcome)
+                .map(|other| game.score(opponent, other))
+                .max();
+            assert_eq!(Some(game.score(opponent, response)), best);
+        }
+    }
+}
+
+#[test]
+fn test_game_errors() {
+    assert_eq!(Game::cyclic(4), Err(GameError::EvenShapes(4)));
+    assert_eq!(Game::cyclic(0), Err(GameError::EvenShapes(0)));
+    assert_eq!(Game::cyclic(27), Err(GameError::TooManyShapes(27)));
+    assert!(Game::cyclic(25).is_ok());
+    assert_eq!(
+        GameError::EvenShapes(4).to_string(),
+        "a game needs an odd number of shapes, not 4"
+    );
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy(
+        "A Y
+B X
+C Z",
+    );
+    assert_eq!(part_1(&input), 15);
+    assert_eq!(part_2(&input), 12);
+    assert_eq!(try_part_1(&input), Ok(15));
+}
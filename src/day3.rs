@@ -1,54 +1,176 @@
-use std::u8;
+use crate::solution::Solution;
+use crate::util::{normalize_input, ParseError, ParseErrorKind, Scanner};
 
-fn letter_score(letter: u8) -> usize {
+pub fn letter_score(letter: u8) -> usize {
     usize::from(letter & 0b0001_1111) +
         // Check if uppercase or lowercase
         if (letter & 0b0010_0000) == 0 { 26 } else { 0 }
 }
 
-fn bag_fingerprint(line: &[u8]) -> u64 {
+pub fn bag_fingerprint(line: &[u8]) -> u64 {
     line.iter().fold(0, |fingerprint, &item| {
         fingerprint | (1 << (item & 0b0011_1111))
     })
 }
 
-fn to_letter(fingerprint: u64) -> u8 {
+pub fn to_letter(fingerprint: u64) -> u8 {
     fingerprint.trailing_zeros() as u8
 }
 
-pub fn part_1(input: &str) -> usize {
+/// The items in the two compartments of a rucksack, as fingerprints.
+#[derive(Debug, Copy, Clone)]
+pub struct Rucksack([u64; 2]);
+
+impl Rucksack {
+    fn from_line(line: &[u8]) -> Self {
+        let (compartment_1, compartment_2) = line.split_at(line.len() / 2);
+        Rucksack([
+            bag_fingerprint(compartment_1),
+            bag_fingerprint(compartment_2),
+        ])
+    }
+
+    fn common_items(self) -> u64 {
+        self.0[0] & self.0[1]
+    }
+
+    fn items(self) -> u64 {
+        self.0[0] | self.0[1]
+    }
+}
+
+pub fn parse(input: &str) -> Vec<Rucksack> {
     input
         // Split lines
         .split_terminator('\n')
         // Map from string to bytes
         .map(str::as_bytes)
-        // Split into two
-        .map(|line| line.split_at(line.len() / 2))
+        .map(Rucksack::from_line)
+        .collect()
+}
+
+pub fn solve_part_1(rucksacks: &[Rucksack]) -> usize {
+    rucksacks
+        .iter()
         // Find duplicates in both parts
-        .map(|(compartment_1, compartment_2)| {
-            bag_fingerprint(compartment_1) & bag_fingerprint(compartment_2)
-        })
+        .map(|rucksack| rucksack.common_items())
         .map(to_letter)
         .map(letter_score)
         .sum()
 }
 
-pub fn part_2(input: &str) -> usize {
-    input
-        .split_terminator('\n')
-        .map(str::as_bytes)
-        .array_chunks::<3>()
-        .map(|arr| {
-            arr.into_iter()
-                .map(bag_fingerprint)
-                .reduce(|acc, new| acc & new)
-                .unwrap_or(0)
+pub fn solve_part_2(rucksacks: &[Rucksack]) -> usize {
+    rucksacks
+        // Split into groups of three
+        .chunks_exact(3)
+        .map(|group| {
+            group
+                .iter()
+                .map(|rucksack| rucksack.items())
+                .fold(u64::MAX, |acc, new| acc & new)
         })
         .map(to_letter)
         .map(letter_score)
         .sum()
 }
 
+pub fn part_1(input: &str) -> usize {
+    let input = &normalize_input(input);
+    solve_part_1(&parse(input))
+}
+
+pub fn part_2(input: &str) -> usize {
+    let input = &normalize_input(input);
+    solve_part_2(&parse(input))
+}
+
+/// Parse the rucksacks, with the offset at which each starts.
+pub fn try_rucksacks(input: &str) -> Result<Vec<(usize, &[u8])>, ParseError> {
+    let mut scanner = Scanner::new(input);
+    let mut rucksacks = Vec::new();
+    while !scanner.is_at_end() {
+        let start = scanner.position();
+        let items = scanner.take_while(|b| b.is_ascii_alphabetic());
+        if items.is_empty() {
+            return Err(scanner.expected("item"));
+        }
+        scanner.line_end()?;
+        rucksacks.push((start, items));
+    }
+    Ok(rucksacks)
+}
+
+pub fn try_part_1(input: &str) -> Result<usize, ParseError> {
+    let input = &normalize_input(input);
+    let invalid = |start, message| ParseError::at(input, start, ParseErrorKind::Invalid(message));
+    let rucksacks = try_rucksacks(input)?;
+    rucksacks
+        .into_iter()
+        .map(|(start, line)| {
+            if line.len() % 2 != 0 {
+                return Err(invalid(start, "compartments differ in size"));
+            }
+            let (compartment_1, compartment_2) = line.split_at(line.len() / 2);
+            match bag_fingerprint(compartment_1) & bag_fingerprint(compartment_2) {
+                0 => Err(invalid(start, "no item in both compartments")),
+                common => Ok(letter_score(to_letter(common))),
+            }
+        })
+        .sum()
+}
+
+pub fn try_part_2(input: &str) -> Result<usize, ParseError> {
+    let input = &normalize_input(input);
+    let invalid = |start, message| ParseError::at(input, start, ParseErrorKind::Invalid(message));
+    let rucksacks = try_rucksacks(input)?;
+    rucksacks
+        .chunks(3)
+        .map(|group| {
+            let start = group[0].0;
+            if group.len() != 3 {
+                return Err(invalid(start, "incomplete group"));
+            }
+            match group
+                .iter()
+                .map(|(_, line)| bag_fingerprint(line))
+                .fold(u64::MAX, |acc, new| acc & new)
+            {
+                0 => Err(invalid(start, "no item common to the group")),
+                common => Ok(letter_score(to_letter(common))),
+            }
+        })
+        .sum()
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    type Parsed<'a> = Vec<Rucksack>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Vec<Rucksack> {
+        parse(input)
+    }
+
+    fn part_1(rucksacks: &Vec<Rucksack>) -> usize {
+        solve_part_1(rucksacks)
+    }
+
+    fn part_2(rucksacks: &Vec<Rucksack>) -> usize {
+        solve_part_2(rucksacks)
+    }
+
+    fn try_part_1(input: &str) -> Result<usize, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<usize, ParseError> {
+        try_part_2(input)
+    }
+}
+
 #[test]
 fn test_letter_scores() {
     for (score, letter) in (1..=26).zip(b'a'..=b'z') {
@@ -92,3 +214,42 @@ fn test_part_2() {
     let input = include_str!("../input/2022/day3.txt");
     assert_eq!(part_2(input), 2434);
 }
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(3);
+}
+
+#[test]
+fn test_try_parts() {
+    let input = include_str!("../input/2022/day3.txt");
+    assert_eq!(try_part_1(input), Ok(part_1(input)));
+    assert_eq!(try_part_2(input), Ok(part_2(input)));
+    assert_eq!(
+        try_part_1("abcA\nab1a\n").unwrap_err().to_string(),
+        "line 2, column 3: expected newline, found '1'"
+    );
+    assert_eq!(
+        try_part_1("abab\nabcd\n").unwrap_err().to_string(),
+        "line 2, column 1: no item in both compartments"
+    );
+    assert_eq!(
+        try_part_2("abab\nabcd\n").unwrap_err().to_string(),
+        "line 1, column 1: incomplete group"
+    );
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy(
+        "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw",
+    );
+    assert_eq!(part_1(&input), 157);
+    assert_eq!(part_2(&input), 70);
+    assert_eq!(try_part_2(&input), Ok(70));
+}
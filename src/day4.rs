@@ -1,7 +1,10 @@
 use std::cmp::Ordering;
 
+use crate::solution::Solution;
+use crate::util::{normalize_input, ParseError, Scanner};
+
 #[derive(Debug)]
-struct Range(u8, u8);
+pub struct Range(u8, u8);
 
 fn one_or_two_digits_to_number(s: &str) -> u8 {
     match *s.as_bytes() {
@@ -12,7 +15,7 @@ fn one_or_two_digits_to_number(s: &str) -> u8 {
 }
 
 impl Range {
-    fn parse(s: &str) -> Range {
+    pub fn parse(s: &str) -> Range {
         let (a, b) = s.split_once('-').unwrap();
         Range(
             one_or_two_digits_to_number(a),
@@ -20,7 +23,22 @@ impl Range {
         )
     }
 
-    fn contains_or_is_contained_by(&self, other: &Range) -> bool {
+    /// Parse a range, keeping the plain section numbers. Since only the
+    /// order of the bounds matters, these compare the same as the encoded
+    /// ones from `parse`.
+    pub fn try_parse(scanner: &mut Scanner) -> Result<Range, ParseError> {
+        let start_position = scanner.position();
+        // The fast path reads at most two digits per section.
+        let start = scanner.small_number()?;
+        scanner.tag("-")?;
+        let end = scanner.small_number()?;
+        if start > end {
+            return Err(scanner.invalid_at(start_position, "range ends before it starts"));
+        }
+        Ok(Range(start, end))
+    }
+
+    pub fn contains_or_is_contained_by(&self, other: &Range) -> bool {
         // True, except if both are greater or less.
         !matches!(
             (self.0.cmp(&other.0), self.1.cmp(&other.1)),
@@ -28,29 +46,95 @@ impl Range {
         )
     }
 
-    fn overlaps_with(&self, other: &Range) -> bool {
+    pub fn overlaps_with(&self, other: &Range) -> bool {
         self.0 <= other.1 && other.0 <= self.1
     }
 }
 
-pub fn part_1(input: &str) -> usize {
+pub fn parse(input: &str) -> Vec<(Range, Range)> {
     input
         .split_terminator('\n')
         .map(|line| line.split_once(',').unwrap())
         .map(|(range_1, range_2)| (Range::parse(range_1), Range::parse(range_2)))
+        .collect()
+}
+
+pub fn solve_part_1(pairs: &[(Range, Range)]) -> usize {
+    pairs
+        .iter()
         .filter(|(range_1, range_2)| range_1.contains_or_is_contained_by(range_2))
         .count()
 }
 
-pub fn part_2(input: &str) -> usize {
-    input
-        .split_terminator('\n')
-        .map(|line| line.split_once(',').unwrap())
-        .map(|(range_1, range_2)| (Range::parse(range_1), Range::parse(range_2)))
+pub fn solve_part_2(pairs: &[(Range, Range)]) -> usize {
+    pairs
+        .iter()
         .filter(|(range_1, range_2)| range_1.overlaps_with(range_2))
         .count()
 }
 
+pub fn part_1(input: &str) -> usize {
+    let input = &normalize_input(input);
+    solve_part_1(&parse(input))
+}
+
+pub fn part_2(input: &str) -> usize {
+    let input = &normalize_input(input);
+    solve_part_2(&parse(input))
+}
+
+pub fn try_range_pairs(input: &str) -> Result<Vec<(Range, Range)>, ParseError> {
+    let mut scanner = Scanner::new(input);
+    let mut pairs = Vec::new();
+    while !scanner.is_at_end() {
+        let range_1 = Range::try_parse(&mut scanner)?;
+        scanner.tag(",")?;
+        let range_2 = Range::try_parse(&mut scanner)?;
+        scanner.line_end()?;
+        pairs.push((range_1, range_2));
+    }
+    Ok(pairs)
+}
+
+pub fn try_part_1(input: &str) -> Result<usize, ParseError> {
+    let input = &normalize_input(input);
+    Ok(solve_part_1(&try_range_pairs(input)?))
+}
+
+pub fn try_part_2(input: &str) -> Result<usize, ParseError> {
+    let input = &normalize_input(input);
+    Ok(solve_part_2(&try_range_pairs(input)?))
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    type Parsed<'a> = Vec<(Range, Range)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Vec<(Range, Range)> {
+        parse(input)
+    }
+
+    fn part_1(pairs: &Vec<(Range, Range)>) -> usize {
+        solve_part_1(pairs)
+    }
+
+    fn part_2(pairs: &Vec<(Range, Range)>) -> usize {
+        solve_part_2(pairs)
+    }
+
+    fn try_part_1(input: &str) -> Result<usize, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<usize, ParseError> {
+        try_part_2(input)
+    }
+}
+
 #[test]
 fn test_part_1_example() {
     let input = "2-4,6-8
@@ -93,3 +177,72 @@ fn test_part_2() {
     let input = include_str!("../input/2022/day4.txt");
     assert_eq!(part_2(input), 917);
 }
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(4);
+}
+
+#[test]
+fn test_try_parts() {
+    let input = include_str!("../input/2022/day4.txt");
+    assert_eq!(try_part_1(input), Ok(part_1(input)));
+    assert_eq!(try_part_2(input), Ok(part_2(input)));
+    assert_eq!(
+        try_part_1("2-4,6-8\n100-200,150-180")
+            .unwrap_err()
+            .to_string(),
+        "line 2, column 1: number out of range"
+    );
+    assert_eq!(
+        try_part_1("2-4,6-8\n10-20,15-300").unwrap_err().to_string(),
+        "line 2, column 10: number out of range"
+    );
+    assert_eq!(
+        try_part_1("2-4,6-8\n2-3;4-5\n").unwrap_err().to_string(),
+        "line 2, column 4: expected \",\", found ';'"
+    );
+    assert_eq!(
+        try_part_1("2-4,6-8\n2-3,4-").unwrap_err().to_string(),
+        "line 2, column 7: expected digit, found end of input"
+    );
+    assert_eq!(
+        try_part_2("2-4,8-6\n").unwrap_err().to_string(),
+        "line 1, column 5: range ends before it starts"
+    );
+}
+
+#[test]
+fn test_accepted_input_runs_on_the_fast_path() {
+    for input in [
+        include_str!("../input/2022/day4.txt"),
+        "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n",
+        "0-99,99-99\n9-10,10-99",
+        "1-1,1-1",
+        "2-4,6-8\n100-200,150-180",
+        "2-4,6-8\n99-100,1-2",
+        "",
+    ] {
+        if let Ok(answer) = try_part_1(input) {
+            assert_eq!(part_1(input), answer, "part 1 of {input:?}");
+        }
+        if let Ok(answer) = try_part_2(input) {
+            assert_eq!(part_2(input), answer, "part 2 of {input:?}");
+        }
+    }
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy(
+        "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8",
+    );
+    assert_eq!(part_1(&input), 2);
+    assert_eq!(part_2(&input), 4);
+    assert_eq!(try_part_1(&input), Ok(2));
+}
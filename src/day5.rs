@@ -1,10 +1,21 @@
 use primitive_types::U256;
 
-pub struct PrintableArray<const SIZE: usize>([u8; SIZE]);
+use crate::solution::Solution;
+use crate::util::{normalize_input, ParseError, Scanner};
+
+/// The most crates a stack can hold, as every crate takes five bits.
+pub const MAX_STACK_HEIGHT: usize = 256 / 5;
+
+/// The top crates of the stacks. Only the first `len` letters are used, as an
+/// input may have fewer stacks than there is room for.
+pub struct PrintableArray<const SIZE: usize> {
+    letters: [u8; SIZE],
+    len: usize,
+}
 
 impl<const SIZE: usize> std::fmt::Display for PrintableArray<SIZE> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for c in self.0 {
+        for &c in &self.letters[..self.len] {
             write!(f, "{}", char::from(c))?;
         }
         Ok(())
@@ -12,7 +23,7 @@ impl<const SIZE: usize> std::fmt::Display for PrintableArray<SIZE> {
 }
 
 #[derive(Clone)]
-struct Warehouse<const STACKS: usize>([U256; STACKS]);
+pub struct Warehouse<const STACKS: usize>([U256; STACKS]);
 
 impl<const STACKS: usize> std::fmt::Debug for Warehouse<STACKS> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -32,9 +43,17 @@ impl<const STACKS: usize> std::fmt::Debug for Warehouse<STACKS> {
 }
 
 impl<const STACKS: usize> Warehouse<STACKS> {
-    fn parse<'a>(lines: &mut impl Iterator<Item = &'a [u8]>) -> Self {
+    /// Parse the drawing of the stacks, including the line of stack numbers.
+    /// Returns the warehouse and the number of stacks.
+    pub fn parse<'a>(lines: &mut impl Iterator<Item = &'a [u8]>) -> (Self, usize) {
         let mut warehouse = Warehouse([U256::zero(); STACKS]);
-        for line in lines.take_while(|s| s[1] != b'1') {
+        let mut stacks = 0;
+        for line in lines {
+            if line[1] == b'1' {
+                // Every stack number but the last is followed by three spaces.
+                stacks = ((line.len() + 2) / 4).min(STACKS);
+                break;
+            }
             for (letter, dest) in line
                 .iter()
                 .skip(1)
@@ -47,7 +66,83 @@ impl<const STACKS: usize> Warehouse<STACKS> {
             }
         }
         warehouse.invert_stacks();
-        warehouse
+        (warehouse, stacks)
+    }
+
+    /// Parse the drawing of the stacks, including the line of stack numbers.
+    /// Returns the warehouse, the number of stacks and the height of each.
+    pub fn try_parse(scanner: &mut Scanner) -> Result<(Self, usize, [usize; STACKS]), ParseError> {
+        let mut warehouse = Warehouse([U256::zero(); STACKS]);
+        let mut heights = [0; STACKS];
+        // The line of stack numbers starts with a space followed by a digit.
+        while !matches!(scanner.rest(), [b' ', d, ..] if d.is_ascii_digit()) {
+            let mut stack = 0;
+            loop {
+                let position = scanner.position();
+                if stack == STACKS {
+                    return Err(scanner.invalid_at(position, "too many stacks"));
+                }
+                if scanner.peek() == Some(b'[') {
+                    scanner.tag("[")?;
+                    let letter = scanner.one_of(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ", "crate")?;
+                    scanner.tag("]")?;
+                    if heights[stack] == MAX_STACK_HEIGHT {
+                        return Err(scanner.invalid_at(position, "stack too high"));
+                    }
+                    heights[stack] += 1;
+                    warehouse.0[stack] = (warehouse.0[stack] << 5) | U256::from(letter & 0b1_1111);
+                } else {
+                    scanner.tag("   ")?;
+                }
+                stack += 1;
+                // Trailing spaces are optional
+                if matches!(scanner.peek(), Some(b'\n') | None) {
+                    break;
+                }
+                scanner.tag(" ")?;
+            }
+            scanner.line_end()?;
+        }
+        warehouse.invert_stacks();
+
+        let mut stacks = 0;
+        loop {
+            scanner.tag(" ")?;
+            let position = scanner.position();
+            if stacks == STACKS {
+                return Err(scanner.invalid_at(position, "too many stacks"));
+            }
+            if scanner.number::<usize>()? != stacks + 1 {
+                return Err(scanner.invalid_at(position, "stacks are not numbered in order"));
+            }
+            stacks += 1;
+            if matches!(scanner.peek(), Some(b'\n') | None) {
+                break;
+            }
+            scanner.tag(" ")?;
+            if matches!(scanner.peek(), Some(b'\n') | None) {
+                break;
+            }
+            scanner.tag(" ")?;
+        }
+        scanner.line_end()?;
+        Ok((warehouse, stacks, heights))
+    }
+
+    /// Take the top crate off each of the first `stacks` stacks. Empty stacks
+    /// are shown as a space.
+    pub fn top_crates(&mut self, stacks: usize) -> PrintableArray<STACKS> {
+        let mut letters = [b' '; STACKS];
+        for (from, out) in letters.iter_mut().enumerate().take(stacks) {
+            match self.pop_crate(from) {
+                0 => {}
+                letter => *out = 0b0100_0000 | letter,
+            }
+        }
+        PrintableArray {
+            letters,
+            len: stacks,
+        }
     }
 
     fn invert_stacks(&mut self) {
@@ -64,23 +159,23 @@ impl<const STACKS: usize> Warehouse<STACKS> {
         *self = new_warehouse;
     }
 
-    fn pop_crate(&mut self, from: usize) -> u8 {
+    pub fn pop_crate(&mut self, from: usize) -> u8 {
         let letter = self.0[from] & U256::from(0b1_1111);
         self.0[from] >>= 5;
         letter.as_u32() as u8
     }
 
-    fn push_crate(&mut self, to: usize, letter: u8) {
+    pub fn push_crate(&mut self, to: usize, letter: u8) {
         debug_assert!((1..=26).contains(&letter), "pushing invalid letter!");
         self.0[to] = (self.0[to] << 5) | U256::from(letter);
     }
 
-    fn move_crate(&mut self, from: usize, to: usize) {
+    pub fn move_crate(&mut self, from: usize, to: usize) {
         let letter = self.pop_crate(from);
         self.push_crate(to, letter);
     }
 
-    fn move_multiple_crates(&mut self, count: u8, from: usize, to: usize) {
+    pub fn move_multiple_crates(&mut self, count: u8, from: usize, to: usize) {
         let bits = 5 * u32::from(count);
         let mask = U256::MAX >> (256 - bits);
         let letters = self.0[from] & mask;
@@ -100,6 +195,76 @@ fn parse_number(s: &mut &[u8]) -> u8 {
     acc
 }
 
+/// A move of crates between two stacks, which are counted from 0.
+#[derive(Debug, Copy, Clone)]
+pub struct Move {
+    pub count: u8,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// The warehouse before the rearrangement, and the moves that make it up.
+#[derive(Debug, Clone)]
+pub struct Procedure<const STACKS: usize> {
+    warehouse: Warehouse<STACKS>,
+    stacks: usize,
+    moves: Vec<Move>,
+}
+
+impl<const STACKS: usize> Procedure<STACKS> {
+    pub fn parse(s: &str) -> Self {
+        let mut lines = s.split_terminator('\n').map(str::as_bytes);
+        let (warehouse, stacks) = Warehouse::<STACKS>::parse(&mut lines);
+        let moves = lines
+            .skip(1)
+            .map(|line| {
+                let mut line = &line[5..];
+                let count = parse_number(&mut line);
+                let from = usize::from(line[6] & 0b1111);
+                let to = usize::from(line[11] & 0b1111);
+                Move {
+                    count,
+                    from: from - 1,
+                    to: to - 1,
+                }
+            })
+            .collect();
+        Procedure {
+            warehouse,
+            stacks,
+            moves,
+        }
+    }
+
+    /// Carry out the moves on a copy of the warehouse and return the top
+    /// crates. If `CHANGE_ORDER` is set, crates are moved one at a time.
+    pub fn top_crates<const CHANGE_ORDER: bool>(&self) -> PrintableArray<STACKS> {
+        let mut warehouse = self.warehouse.clone();
+        for &Move { count, from, to } in &self.moves {
+            if CHANGE_ORDER {
+                for _ in 0..count {
+                    warehouse.move_crate(from, to);
+                }
+            } else {
+                warehouse.move_multiple_crates(count, from, to);
+            }
+        }
+        warehouse.top_crates(self.stacks)
+    }
+}
+
+pub fn parse(s: &str) -> Procedure<9> {
+    Procedure::parse(s)
+}
+
+pub fn solve_part_1(procedure: &Procedure<9>) -> PrintableArray<9> {
+    procedure.top_crates::<true>()
+}
+
+pub fn solve_part_2(procedure: &Procedure<9>) -> PrintableArray<9> {
+    procedure.top_crates::<false>()
+}
+
 pub fn part_1(s: &str) -> PrintableArray<9> {
     solve_generic::<9, true>(s)
 }
@@ -111,26 +276,91 @@ pub fn part_2(s: &str) -> PrintableArray<9> {
 pub fn solve_generic<const STACKS: usize, const CHANGE_ORDER: bool>(
     s: &str,
 ) -> PrintableArray<STACKS> {
-    let mut lines = s.split_terminator('\n').map(str::as_bytes);
-    let mut warehouse = Warehouse::<STACKS>::parse(&mut lines);
-    for line in lines.skip(1) {
-        let mut line = &line[5..];
-        let count = parse_number(&mut line);
-        let from = usize::from(line[6] & 0b1111);
-        let to = usize::from(line[11] & 0b1111);
+    let s = &normalize_input(s);
+    Procedure::<STACKS>::parse(s).top_crates::<CHANGE_ORDER>()
+}
+
+/// Read a stack number, returning its index.
+fn try_stack(scanner: &mut Scanner, stacks: usize) -> Result<usize, ParseError> {
+    let position = scanner.position();
+    match scanner.number::<usize>()? {
+        stack @ 1.. if stack <= stacks => Ok(stack - 1),
+        _ => Err(scanner.invalid_at(position, "no such stack")),
+    }
+}
+
+pub fn try_solve_generic<const STACKS: usize, const CHANGE_ORDER: bool>(
+    s: &str,
+) -> Result<PrintableArray<STACKS>, ParseError> {
+    let s = &normalize_input(s);
+    let mut scanner = Scanner::new(s);
+    let (mut warehouse, stacks, mut heights) = Warehouse::<STACKS>::try_parse(&mut scanner)?;
+    scanner.line_end()?;
+    while !scanner.is_at_end() {
+        let start = scanner.position();
+        scanner.tag("move ")?;
+        let count = scanner.number::<u8>()?;
+        scanner.tag(" from ")?;
+        let from = try_stack(&mut scanner, stacks)?;
+        scanner.tag(" to ")?;
+        let to = try_stack(&mut scanner, stacks)?;
+        scanner.line_end()?;
+
+        let count_usize = usize::from(count);
+        if count_usize > heights[from] {
+            return Err(scanner.invalid_at(start, "not enough crates to move"));
+        }
+        heights[from] -= count_usize;
+        if heights[to] + count_usize > MAX_STACK_HEIGHT {
+            return Err(scanner.invalid_at(start, "stack too high"));
+        }
+        heights[to] += count_usize;
         if CHANGE_ORDER {
             for _ in 0..count {
-                warehouse.move_crate(from - 1, to - 1);
+                warehouse.move_crate(from, to);
             }
         } else {
-            warehouse.move_multiple_crates(count, from - 1, to - 1);
+            warehouse.move_multiple_crates(count, from, to);
         }
     }
-    let mut output = [0b0100_0000; STACKS];
-    for (from, out) in output.iter_mut().enumerate() {
-        *out |= warehouse.pop_crate(from);
+    Ok(warehouse.top_crates(stacks))
+}
+
+pub fn try_part_1(s: &str) -> Result<PrintableArray<9>, ParseError> {
+    try_solve_generic::<9, true>(s)
+}
+
+pub fn try_part_2(s: &str) -> Result<PrintableArray<9>, ParseError> {
+    try_solve_generic::<9, false>(s)
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+    type Parsed<'a> = Procedure<9>;
+    type Answer1 = PrintableArray<9>;
+    type Answer2 = PrintableArray<9>;
+
+    fn parse(input: &str) -> Procedure<9> {
+        parse(input)
+    }
+
+    fn part_1(procedure: &Procedure<9>) -> PrintableArray<9> {
+        solve_part_1(procedure)
+    }
+
+    fn part_2(procedure: &Procedure<9>) -> PrintableArray<9> {
+        solve_part_2(procedure)
+    }
+
+    fn try_part_1(input: &str) -> Result<PrintableArray<9>, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<PrintableArray<9>, ParseError> {
+        try_part_2(input)
     }
-    PrintableArray(output)
 }
 
 #[test]
@@ -172,3 +402,77 @@ fn test_part_2() {
     let input = include_str!("../input/2022/day5.txt");
     assert_eq!(part_2(input).to_string(), "QZFJRWHGS");
 }
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(5);
+}
+
+#[test]
+fn test_try_parts() {
+    let input = include_str!("../input/2022/day5.txt");
+    assert_eq!(try_part_1(input).unwrap().to_string(), "ZSQVCCJLL");
+    assert_eq!(try_part_2(input).unwrap().to_string(), "QZFJRWHGS");
+}
+
+#[test]
+fn test_try_parse_errors() {
+    let drawing = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+";
+    let error = |moves: &str| {
+        try_solve_generic::<3, true>(&format!("{drawing}{moves}"))
+            .map(|top| top.to_string())
+            .unwrap_err()
+            .to_string()
+    };
+    assert_eq!(
+        try_solve_generic::<3, true>(&format!("{drawing}move 1 from 2 to 1"))
+            .unwrap()
+            .to_string(),
+        "DCP"
+    );
+    assert_eq!(
+        error("move 1 from 4 to 1"),
+        "line 6, column 13: no such stack"
+    );
+    assert_eq!(
+        error("move 4 from 2 to 1\n"),
+        "line 6, column 1: not enough crates to move"
+    );
+    assert_eq!(
+        error("move 1 form 2 to 1\n"),
+        "line 6, column 9: expected \" from \", found 'o'"
+    );
+    assert_eq!(
+        try_solve_generic::<2, true>(&format!("{drawing}move 1 from 2 to 1"))
+            .map(|top| top.to_string())
+            .unwrap_err()
+            .to_string(),
+        "line 1, column 9: too many stacks"
+    );
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy(
+        "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2",
+    );
+    assert_eq!(solve_generic::<3, true>(&input).to_string(), "CMZ");
+    assert_eq!(solve_generic::<3, false>(&input).to_string(), "MCD");
+    assert_eq!(
+        try_solve_generic::<3, true>(&input).unwrap().to_string(),
+        "CMZ"
+    );
+}
@@ -1,3 +1,6 @@
+use crate::solution::Solution;
+use crate::util::{normalize_input, ParseError, Scanner};
+
 fn all_distinct<const SIZE: usize>(window: &[u8; SIZE]) -> bool {
     for i in 0..SIZE {
         for j in (i + 1)..SIZE {
@@ -9,22 +12,95 @@ fn all_distinct<const SIZE: usize>(window: &[u8; SIZE]) -> bool {
     true
 }
 
-fn find_marker<const SIZE: usize>(s: &str) -> usize {
-    s.as_bytes()
-        .array_windows::<SIZE>()
+pub fn find_marker<const SIZE: usize>(s: &str) -> usize {
+    try_find_marker::<SIZE>(s.as_bytes()).unwrap()
+}
+
+pub fn try_find_marker<const SIZE: usize>(s: &[u8]) -> Option<usize> {
+    #[cfg(feature = "nightly")]
+    let windows = s.array_windows::<SIZE>();
+    #[cfg(not(feature = "nightly"))]
+    let windows = s
+        .windows(SIZE)
+        .map(|window| <&[u8; SIZE]>::try_from(window).unwrap());
+    windows
         .enumerate()
         .find(|(_, window)| all_distinct(window))
-        .unwrap()
-        .0
-        + SIZE
+        .map(|(index, _)| index + SIZE)
+}
+
+/// Check that the input is a single line of letters, and find the marker.
+fn try_find_marker_in_input<const SIZE: usize>(s: &str) -> Result<usize, ParseError> {
+    let mut scanner = Scanner::new(s);
+    let signal = scanner.take_while(|b| b.is_ascii_lowercase());
+    scanner.line_end()?;
+    if !scanner.is_at_end() {
+        return Err(scanner.expected("end of input"));
+    }
+    try_find_marker::<SIZE>(signal)
+        .ok_or_else(|| scanner.invalid_at(signal.len(), "no marker in the signal"))
+}
+
+/// The signal, without the line ending.
+pub fn parse(s: &str) -> &[u8] {
+    s.trim_end().as_bytes()
+}
+
+pub fn solve_part_1(signal: &[u8]) -> usize {
+    try_find_marker::<4>(signal).unwrap()
+}
+
+pub fn solve_part_2(signal: &[u8]) -> usize {
+    try_find_marker::<14>(signal).unwrap()
 }
 
 pub fn part_1(s: &str) -> usize {
-    find_marker::<4>(s)
+    let s = &normalize_input(s);
+    solve_part_1(parse(s))
 }
 
 pub fn part_2(s: &str) -> usize {
-    find_marker::<14>(s)
+    let s = &normalize_input(s);
+    solve_part_2(parse(s))
+}
+
+pub fn try_part_1(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    try_find_marker_in_input::<4>(s)
+}
+
+pub fn try_part_2(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    try_find_marker_in_input::<14>(s)
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+    type Parsed<'a> = &'a [u8];
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> &'_ [u8] {
+        parse(input)
+    }
+
+    fn part_1(signal: &&'_ [u8]) -> usize {
+        solve_part_1(signal)
+    }
+
+    fn part_2(signal: &&'_ [u8]) -> usize {
+        solve_part_2(signal)
+    }
+
+    fn try_part_1(input: &str) -> Result<usize, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<usize, ParseError> {
+        try_part_2(input)
+    }
 }
 
 #[test]
@@ -61,3 +137,32 @@ fn test_part_2() {
     let input = include_str!("../input/2022/day6.txt");
     assert_eq!(part_2(input), 2508);
 }
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(6);
+}
+
+#[test]
+fn test_try_parts() {
+    let input = include_str!("../input/2022/day6.txt");
+    assert_eq!(try_part_1(input), Ok(part_1(input)));
+    assert_eq!(try_part_2(input), Ok(part_2(input)));
+    assert_eq!(try_part_1("abcd\n"), Ok(4));
+    assert_eq!(
+        try_part_1("abab").unwrap_err().to_string(),
+        "line 1, column 5: no marker in the signal"
+    );
+    assert_eq!(
+        try_part_1("abcD").unwrap_err().to_string(),
+        "line 1, column 4: expected newline, found 'D'"
+    );
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy("mjqjpqmgbljsphdztnvjfqwrcgsmlb");
+    assert_eq!(part_1(&input), 7);
+    assert_eq!(part_2(&input), 19);
+    assert_eq!(try_part_1(&input), Ok(7));
+}
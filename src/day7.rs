@@ -1,20 +1,23 @@
 use std::{collections::HashMap, fmt::Debug, str::from_utf8};
 
-struct FileTree<'a>(HashMap<Path<'a>, Directory<'a>>);
+use crate::solution::Solution;
+use crate::util::{normalize_input, ParseError, Scanner};
+
+pub struct FileTree<'a>(HashMap<Path<'a>, Directory<'a>>);
 
 #[derive(Clone, Default, Hash, Eq, PartialEq)]
-struct Path<'a>(Vec<&'a [u8]>);
+pub struct Path<'a>(Vec<&'a [u8]>);
 
 impl<'a> Path<'a> {
-    fn go_up(&mut self) {
+    pub fn go_up(&mut self) {
         self.0.pop();
     }
 
-    fn go_to_root(&mut self) {
+    pub fn go_to_root(&mut self) {
         self.0.clear();
     }
 
-    fn go_to_subdir(&mut self, subdir: &'a [u8]) {
+    pub fn go_to_subdir(&mut self, subdir: &'a [u8]) {
         self.0.push(subdir);
     }
 }
@@ -42,7 +45,7 @@ struct SizeInfo {
 }
 
 impl<'a> FileTree<'a> {
-    fn build(s: &'a str) -> Self {
+    pub fn build(s: &'a str) -> Self {
         let mut map: HashMap<Path<'_>, Directory<'_>> = HashMap::new();
         let lines = s.split_terminator('\n').map(str::as_bytes);
         let mut current_path = Path::default();
@@ -87,7 +90,86 @@ impl<'a> FileTree<'a> {
         FileTree(map)
     }
 
-    fn total_size_of_small_directories(&self) -> usize {
+    /// Like `build`, but returns an error for malformed lines, for listings
+    /// outside of `ls`, and for directories whose contents are never listed.
+    pub fn try_build(s: &'a str) -> Result<Self, ParseError> {
+        let mut scanner = Scanner::new(s);
+        let mut map: HashMap<Path<'_>, Directory<'_>> = HashMap::new();
+        let mut current_path = Path::default();
+        let mut directory_info = None;
+        // Every listed subdirectory, with the offset of its listing.
+        let mut listed_subdirectories = Vec::new();
+        let mut total_size = 0;
+        while !scanner.is_at_end() {
+            let start = scanner.position();
+            match scanner.peek() {
+                Some(b'$') => {
+                    scanner.tag("$ ")?;
+                    if let Some(current_dirinfo) = directory_info.take() {
+                        map.insert(current_path.clone(), current_dirinfo);
+                    }
+                    if scanner.peek() == Some(b'l') {
+                        scanner.tag("ls")?;
+                        directory_info = Some(Directory::default());
+                    } else {
+                        scanner.tag("cd ")?;
+                        let position = scanner.position();
+                        match try_name(&mut scanner)? {
+                            b".." => current_path.go_up(),
+                            b"/" => current_path.go_to_root(),
+                            // `build` only looks at the first byte of these.
+                            [b'.' | b'/', ..] => {
+                                return Err(
+                                    scanner.invalid_at(position, "unsupported directory name")
+                                )
+                            }
+                            subdir => current_path.go_to_subdir(subdir),
+                        }
+                    }
+                }
+                Some(b'd') => {
+                    scanner.tag("dir ")?;
+                    let dirname = try_name(&mut scanner)?;
+                    let Some(dirinfo) = directory_info.as_mut() else {
+                        return Err(scanner.invalid_at(start, "listing outside of ls"));
+                    };
+                    dirinfo.subdirs.push(dirname);
+                    let mut path = current_path.clone();
+                    path.go_to_subdir(dirname);
+                    listed_subdirectories.push((path, start));
+                }
+                Some(b'0'..=b'9') => {
+                    let size: usize = scanner.number()?;
+                    total_size += size.min(DISK_SIZE + 1);
+                    if total_size > DISK_SIZE {
+                        return Err(scanner.invalid_at(start, "the files do not fit on the disk"));
+                    }
+                    scanner.tag(" ")?;
+                    try_name(&mut scanner)?;
+                    let Some(dirinfo) = directory_info.as_mut() else {
+                        return Err(scanner.invalid_at(start, "listing outside of ls"));
+                    };
+                    dirinfo.size += size;
+                }
+                _ => return Err(scanner.expected("command, directory or file")),
+            }
+            scanner.line_end()?;
+        }
+        if let Some(current_dirinfo) = directory_info.take() {
+            map.insert(current_path, current_dirinfo);
+        }
+        if !map.contains_key(&Path::default()) {
+            return Err(scanner.invalid_at(0, "the root directory is never listed"));
+        }
+        for (path, offset) in listed_subdirectories {
+            if !map.contains_key(&path) {
+                return Err(scanner.invalid_at(offset, "directory is never listed"));
+            }
+        }
+        Ok(FileTree(map))
+    }
+
+    pub fn total_size_of_small_directories(&self) -> usize {
         self.subdirectory_size_info(&mut Path::default())
             .sum_of_small_subdirectory_sizes
     }
@@ -112,12 +194,12 @@ impl<'a> FileTree<'a> {
         size_info
     }
 
-    fn total_size_used(&self) -> usize {
+    pub fn total_size_used(&self) -> usize {
         self.subdirectory_size_info(&mut Path::default()).total_size
     }
 
     /// Returns the smallest subdirectory above threshold, or potentially itself
-    fn smallest_subdirectory_above_threshold(
+    pub fn smallest_subdirectory_above_threshold(
         &self,
         path: &mut Path<'a>,
         threshold: usize,
@@ -148,18 +230,85 @@ impl<'a> FileTree<'a> {
     }
 }
 
-pub fn part_1(s: &str) -> usize {
-    FileTree::build(s).total_size_of_small_directories()
+const DISK_SIZE: usize = 70_000_000;
+/// The free space needed for the update.
+const SPACE_NEEDED: usize = 30_000_000;
+
+pub fn parse(s: &str) -> FileTree<'_> {
+    FileTree::build(s)
 }
 
-pub fn part_2(s: &str) -> usize {
-    let file_tree = FileTree::build(s);
-    let size_needed = file_tree.total_size_used() + 30_000_000 - 70_000_000;
+pub fn solve_part_1(file_tree: &FileTree) -> usize {
+    file_tree.total_size_of_small_directories()
+}
+
+pub fn solve_part_2(file_tree: &FileTree) -> usize {
+    // If there already is enough space, the threshold is zero.
+    let size_needed = (file_tree.total_size_used() + SPACE_NEEDED).saturating_sub(DISK_SIZE);
     file_tree
         .smallest_subdirectory_above_threshold(&mut Path::default(), size_needed)
         .unwrap_or_default()
 }
 
+pub fn part_1(s: &str) -> usize {
+    let s = &normalize_input(s);
+    solve_part_1(&parse(s))
+}
+
+pub fn part_2(s: &str) -> usize {
+    let s = &normalize_input(s);
+    solve_part_2(&parse(s))
+}
+
+/// Read a file or directory name up to the end of the line.
+fn try_name<'a>(scanner: &mut Scanner<'a>) -> Result<&'a [u8], ParseError> {
+    let name = scanner.take_while(|b| b != b'\n');
+    if name.is_empty() {
+        Err(scanner.expected("name"))
+    } else {
+        Ok(name)
+    }
+}
+
+pub fn try_part_1(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    Ok(solve_part_1(&FileTree::try_build(s)?))
+}
+
+pub fn try_part_2(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    Ok(solve_part_2(&FileTree::try_build(s)?))
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+    type Parsed<'a> = FileTree<'a>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> FileTree<'_> {
+        parse(input)
+    }
+
+    fn part_1(file_tree: &FileTree<'_>) -> usize {
+        solve_part_1(file_tree)
+    }
+
+    fn part_2(file_tree: &FileTree<'_>) -> usize {
+        solve_part_2(file_tree)
+    }
+
+    fn try_part_1(input: &str) -> Result<usize, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<usize, ParseError> {
+        try_part_2(input)
+    }
+}
+
 #[test]
 fn test_part_1_example() {
     let input = "$ cd /
@@ -229,3 +378,77 @@ fn test_part_2() {
     let input = include_str!("../input/2022/day7.txt");
     assert_eq!(part_2(input), 7421137);
 }
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(7);
+}
+
+#[test]
+fn test_try_parts() {
+    let input = include_str!("../input/2022/day7.txt");
+    assert_eq!(try_part_1(input), Ok(part_1(input)));
+    assert_eq!(try_part_2(input), Ok(part_2(input)));
+}
+
+#[test]
+fn test_try_build_errors() {
+    let error = |input| try_part_1(input).unwrap_err().to_string();
+    assert_eq!(
+        error("$ cd /\n$ ls\ndir a\n12 b\n$ cd a\n$ pwd\n"),
+        "line 6, column 3: expected \"cd \", found 'p'"
+    );
+    assert_eq!(
+        error("$ cd /\ndir a\n"),
+        "line 2, column 1: listing outside of ls"
+    );
+    assert_eq!(
+        error("$ cd /\n$ ls\ndir a\n12 b\n"),
+        "line 3, column 1: directory is never listed"
+    );
+    assert_eq!(
+        error("$ cd /\n$ ls\n12\n"),
+        "line 3, column 3: expected \" \", found '\\n'"
+    );
+    assert_eq!(
+        error("$ cd /\n$ ls\n40000000 a\n30000001 b\n"),
+        "line 4, column 1: the files do not fit on the disk"
+    );
+    assert_eq!(try_part_2("$ cd /\n$ ls\n10 a\n"), Ok(10));
+    assert_eq!(
+        error("$ cd /\n$ ls\ndir .a\n$ cd .a\n"),
+        "line 4, column 6: unsupported directory name"
+    );
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy(
+        "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k",
+    );
+    assert_eq!(part_1(&input), 95437);
+    assert_eq!(part_2(&input), 24933642);
+    assert_eq!(try_part_1(&input), Ok(95437));
+}
@@ -1,138 +1,252 @@
-#[derive(Copy, Clone, Debug, Default)]
-struct Index<const WIDTH: usize>(usize);
+use crate::solution::Solution;
+use crate::util::{normalize_input, Direction, Grid, ParseError, ParseErrorKind};
 
-impl<const WIDTH: usize> Index<WIDTH> {
-    fn move_left(&mut self) {
-        self.0 -= 1;
+/// Mark the trees along a line that are visible from its start in `marked`,
+/// which is indexed like the trees.
+fn mark_visible_along(grid: Grid<u8>, marked: &mut [bool], line: impl Iterator<Item = usize>) {
+    let mut current_smallest = b'0' - 1;
+    for position in line {
+        let tree = grid[position];
+        if tree > current_smallest {
+            marked[position] = true;
+            current_smallest = tree;
+        }
     }
+}
 
-    fn move_right(&mut self) {
-        self.0 += 1;
+/// Mark all trees visible from outside the forest. Always inlined, so that
+/// when the caller builds the grid from constant dimensions the loop bounds
+/// are known at compile time.
+#[inline(always)]
+fn mark_visible(grid: Grid<u8>, marked: &mut [bool]) {
+    let (right, bottom) = (grid.width() - 1, grid.height() - 1);
+    for x in 0..grid.width() {
+        mark_visible_along(grid, marked, grid.column(x));
+        mark_visible_along(
+            grid,
+            marked,
+            grid.ray(grid.position(x, bottom), Direction::Up),
+        );
     }
+    for y in 0..grid.height() {
+        mark_visible_along(grid, marked, grid.row(y));
+        mark_visible_along(
+            grid,
+            marked,
+            grid.ray(grid.position(right, y), Direction::Left),
+        );
+    }
+}
 
-    fn move_up(&mut self) {
-        self.0 -= WIDTH + 1;
+/// Multiply the scenic score of every tree along a line by its viewing
+/// distance towards the start of the line.
+fn multiply_scenic_scores_along(
+    grid: Grid<u8>,
+    scenic_score: &mut [usize],
+    line: impl Iterator<Item = usize>,
+) {
+    let mut last_encounter_of_tree_of_at_least_height = [0; 16];
+    for (position_along_line, position) in line.enumerate() {
+        let tree = usize::from(grid[position] & 0b1111);
+        // Compute the view
+        let view = position_along_line - last_encounter_of_tree_of_at_least_height[tree];
+        scenic_score[position] *= view;
+        for last_encounter_of_tree in last_encounter_of_tree_of_at_least_height
+            .iter_mut()
+            .take(tree + 1)
+        {
+            *last_encounter_of_tree = position_along_line;
+        }
     }
+}
 
-    fn move_down(&mut self) {
-        self.0 += WIDTH + 1;
+/// Compute the scenic score of every tree in `scenic_score`, which is indexed
+/// like the trees and should be filled with ones.
+#[inline(always)]
+fn compute_scenic_scores(grid: Grid<u8>, scenic_score: &mut [usize]) {
+    let (right, bottom) = (grid.width() - 1, grid.height() - 1);
+    for x in 0..grid.width() {
+        multiply_scenic_scores_along(grid, scenic_score, grid.column(x));
+        multiply_scenic_scores_along(
+            grid,
+            scenic_score,
+            grid.ray(grid.position(x, bottom), Direction::Up),
+        );
+    }
+    for y in 0..grid.height() {
+        multiply_scenic_scores_along(grid, scenic_score, grid.row(y));
+        multiply_scenic_scores_along(
+            grid,
+            scenic_score,
+            grid.ray(grid.position(right, y), Direction::Left),
+        );
     }
 }
 
 pub fn part_1_generic<const WIDTH: usize, const HEIGHT: usize, const TOTAL_SIZE: usize>(
     s: &str,
 ) -> usize {
-    let trees = s.as_bytes();
-    assert!(trees.len() >= TOTAL_SIZE);
-    debug_assert!(TOTAL_SIZE == (WIDTH + 1) * (HEIGHT - 1) + WIDTH);
-    let mut marked = [false; TOTAL_SIZE];
-    let mut test_and_mark = |index: Index<WIDTH>, current_smallest: &mut u8| {
-        let tree = trees[index.0];
-        if tree > *current_smallest {
-            marked[index.0] = true;
-            *current_smallest = tree;
-        }
-    };
-
-    let mut index = Index::default();
-    for _ in 0..WIDTH {
-        let mut current_smallest = b'0' - 1;
-        for _ in 0..HEIGHT {
-            test_and_mark(index, &mut current_smallest);
-            index.move_down();
-        }
-        // The index is now one too far down.
-        let mut current_smallest = b'0' - 1;
-        for _ in 0..HEIGHT {
-            index.move_up();
-            test_and_mark(index, &mut current_smallest);
-        }
-        index.move_right();
-    }
-
-    index = Index::default();
-    for _ in 0..HEIGHT {
-        let mut current_smallest = b'0' - 1;
-        for _ in 0..WIDTH {
-            test_and_mark(index, &mut current_smallest);
-            index.move_right();
-        }
-        // The index is now one too far to the right
-        let mut current_smallest = b'0' - 1;
-        for _ in 0..WIDTH {
-            index.move_left();
-            test_and_mark(index, &mut current_smallest);
-        }
-        index.move_down();
-    }
-    marked.iter().filter(|b| **b).count()
+    count_visible_sized::<WIDTH, HEIGHT, TOTAL_SIZE>(s.as_bytes())
 }
 
 pub fn part_2_generic<const WIDTH: usize, const HEIGHT: usize, const TOTAL_SIZE: usize>(
     s: &str,
 ) -> usize {
-    let trees = s.as_bytes();
-    assert!(trees.len() >= TOTAL_SIZE);
+    max_scenic_score_sized::<WIDTH, HEIGHT, TOTAL_SIZE>(s.as_bytes())
+}
+
+/// Count the visible trees of a forest whose dimensions are all known at
+/// compile time.
+fn count_visible_sized<const WIDTH: usize, const HEIGHT: usize, const TOTAL_SIZE: usize>(
+    cells: &[u8],
+) -> usize {
+    debug_assert!(TOTAL_SIZE == (WIDTH + 1) * (HEIGHT - 1) + WIDTH);
+    count_visible_generic::<TOTAL_SIZE>(Grid::new(cells, WIDTH, HEIGHT, WIDTH + 1))
+}
+
+/// Find the highest scenic score of a forest whose dimensions are all known
+/// at compile time.
+fn max_scenic_score_sized<const WIDTH: usize, const HEIGHT: usize, const TOTAL_SIZE: usize>(
+    cells: &[u8],
+) -> usize {
     debug_assert!(TOTAL_SIZE == (WIDTH + 1) * (HEIGHT - 1) + WIDTH);
+    max_scenic_score_generic::<TOTAL_SIZE>(Grid::new(cells, WIDTH, HEIGHT, WIDTH + 1))
+}
 
-    // Set indexed like the trees.
+/// Like `count_visible`, but for a grid of `TOTAL_SIZE` cells.
+#[inline(always)]
+fn count_visible_generic<const TOTAL_SIZE: usize>(grid: Grid<u8>) -> usize {
+    let mut marked = [false; TOTAL_SIZE];
+    mark_visible(grid, &mut marked);
+    marked.iter().filter(|b| **b).count()
+}
+
+/// Like `max_scenic_score`, but for a grid of `TOTAL_SIZE` cells.
+#[inline(always)]
+fn max_scenic_score_generic<const TOTAL_SIZE: usize>(grid: Grid<u8>) -> usize {
     let mut scenic_score = [1usize; TOTAL_SIZE];
+    compute_scenic_scores(grid, &mut scenic_score);
+    scenic_score.iter().copied().max().unwrap_or_default()
+}
 
-    let mut test_and_mark =
-        |index: Index<WIDTH>,
-         position_along_line: usize,
-         last_encounter_of_tree_of_at_least_height: &mut [usize; 16]| {
-            let tree = usize::from(trees[index.0] & 0b1111);
-            // Compute the view
-            let view = position_along_line - last_encounter_of_tree_of_at_least_height[tree];
-            scenic_score[index.0] *= view;
-            for last_encounter_of_tree in last_encounter_of_tree_of_at_least_height
-                .iter_mut()
-                .take(tree + 1)
-            {
-                *last_encounter_of_tree = position_along_line;
-            }
-        };
-
-    let mut last_encounter_of_tree_of_at_least_height;
-    let mut index = Index::<WIDTH>::default();
-    for _ in 0..WIDTH {
-        last_encounter_of_tree_of_at_least_height = [0; 16];
-        for y in 0..HEIGHT {
-            test_and_mark(index, y, &mut last_encounter_of_tree_of_at_least_height);
-            index.move_down();
-        }
-        last_encounter_of_tree_of_at_least_height = [0; 16];
-        for y in 0..HEIGHT {
-            index.move_up();
-            test_and_mark(index, y, &mut last_encounter_of_tree_of_at_least_height);
-        }
-        index.move_right();
+/// Like `part_1_generic`, but for a forest of any size.
+pub fn part_1_runtime(s: &str) -> usize {
+    count_visible(Grid::from_input(s))
+}
+
+pub fn count_visible(grid: Grid<u8>) -> usize {
+    let mut marked = vec![false; grid.cells().len()];
+    mark_visible(grid, &mut marked);
+    marked.iter().filter(|b| **b).count()
+}
+
+/// Like `part_2_generic`, but for a forest of any size.
+pub fn part_2_runtime(s: &str) -> usize {
+    max_scenic_score(Grid::from_input(s))
+}
+
+pub fn max_scenic_score(grid: Grid<u8>) -> usize {
+    let mut scenic_score = vec![1usize; grid.cells().len()];
+    compute_scenic_scores(grid, &mut scenic_score);
+    scenic_score.iter().copied().max().unwrap_or_default()
+}
+
+/// Whether the forest is the size of the puzzle input, so that the
+/// const-generic specialisation can be used.
+fn is_puzzle_size(grid: &Grid<u8>) -> bool {
+    grid.width() == 99 && grid.height() == 99 && grid.cells().len() == 9899
+}
+
+pub fn parse(s: &str) -> Grid<'_, u8> {
+    Grid::from_input(s)
+}
+
+pub fn solve_part_1(grid: &Grid<u8>) -> usize {
+    if is_puzzle_size(grid) {
+        // Use literal dimensions rather than the ones stored in the grid, so
+        // that the loop bounds are constant.
+        count_visible_sized::<99, 99, 9899>(grid.cells())
+    } else {
+        count_visible(*grid)
     }
+}
 
-    index = Index::<WIDTH>::default();
-    for _ in 0..HEIGHT {
-        last_encounter_of_tree_of_at_least_height = [0; 16];
-        for x in 0..WIDTH {
-            test_and_mark(index, x, &mut last_encounter_of_tree_of_at_least_height);
-            index.move_right();
-        }
-        last_encounter_of_tree_of_at_least_height = [0; 16];
-        for x in 0..WIDTH {
-            index.move_left();
-            test_and_mark(index, x, &mut last_encounter_of_tree_of_at_least_height);
-        }
-        index.move_down();
+pub fn solve_part_2(grid: &Grid<u8>) -> usize {
+    if is_puzzle_size(grid) {
+        max_scenic_score_sized::<99, 99, 9899>(grid.cells())
+    } else {
+        max_scenic_score(*grid)
     }
-    scenic_score.iter().copied().max().unwrap_or_default()
 }
 
 pub fn part_1(s: &str) -> usize {
-    part_1_generic::<99, 99, 9899>(s)
+    let s = &normalize_input(s);
+    solve_part_1(&parse(s))
 }
 
 pub fn part_2(s: &str) -> usize {
-    part_2_generic::<99, 99, 9899>(s)
+    let s = &normalize_input(s);
+    solve_part_2(&parse(s))
+}
+
+/// Parse the forest, checking that it is rectangular and only contains
+/// digits.
+fn try_forest(s: &str) -> Result<Grid<'_, u8>, ParseError> {
+    let grid = Grid::try_from_input(s)?;
+    match grid
+        .cells()
+        .iter()
+        .position(|b| !b.is_ascii_digit() && *b != b'\n')
+    {
+        Some(offset) => Err(ParseError::at(
+            s,
+            offset,
+            ParseErrorKind::Expected {
+                what: "digit",
+                found: Some(grid.cells()[offset]),
+            },
+        )),
+        None => Ok(grid),
+    }
+}
+
+pub fn try_part_1(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    Ok(count_visible(try_forest(s)?))
+}
+
+pub fn try_part_2(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    Ok(max_scenic_score(try_forest(s)?))
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+    type Parsed<'a> = Grid<'a, u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Grid<'_, u8> {
+        parse(input)
+    }
+
+    fn part_1(grid: &Grid<'_, u8>) -> usize {
+        solve_part_1(grid)
+    }
+
+    fn part_2(grid: &Grid<'_, u8>) -> usize {
+        solve_part_2(grid)
+    }
+
+    fn try_part_1(input: &str) -> Result<usize, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<usize, ParseError> {
+        try_part_2(input)
+    }
 }
 
 #[test]
@@ -166,3 +280,65 @@ fn test_part_2() {
     let input = include_str!("../input/2022/day8.txt");
     assert_eq!(part_2(input), 330786);
 }
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(8);
+}
+
+#[test]
+fn test_runtime_size_example() {
+    let input = "30373
+25512
+65332
+33549
+35390
+";
+    assert_eq!(part_1(input), 21);
+    assert_eq!(part_2(input), 8);
+}
+
+#[test]
+fn test_runtime_size_rectangular() {
+    let input = "3037
+2551
+6533";
+    assert_eq!(part_1_runtime(input), 12);
+    assert_eq!(part_2_runtime(input), 1);
+}
+
+#[test]
+fn test_runtime_size_matches_generic() {
+    let input = include_str!("../input/2022/day8.txt");
+    assert_eq!(part_1_runtime(input), part_1_generic::<99, 99, 9899>(input));
+    assert_eq!(part_2_runtime(input), part_2_generic::<99, 99, 9899>(input));
+}
+
+#[test]
+fn test_try_parts() {
+    let input = include_str!("../input/2022/day8.txt");
+    assert_eq!(try_part_1(input), Ok(part_1(input)));
+    assert_eq!(try_part_2(input), Ok(part_2(input)));
+    assert_eq!(
+        try_part_1("303\n255\n6a3\n").unwrap_err().to_string(),
+        "line 3, column 2: expected digit, found 'a'"
+    );
+    assert_eq!(
+        try_part_2("303\n2553\n633\n").unwrap_err().to_string(),
+        "line 2, column 4: expected newline, found '3'"
+    );
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy(
+        "30373
+25512
+65332
+33549
+35390",
+    );
+    assert_eq!(part_1(&input), 21);
+    assert_eq!(part_2(&input), 8);
+    assert_eq!(try_part_1(&input), Ok(21));
+}
@@ -1,10 +1,13 @@
 use std::collections::HashSet;
 
+use crate::solution::Solution;
+use crate::util::{normalize_input, ParseError, Scanner};
+
 /// Denotes the relative position of the leading part of the rope.
 #[derive(Default, Copy, Clone, Debug, Hash, Eq, PartialEq)]
-struct RopePartPosition {
-    horizontal_diff: i16,
-    vertical_diff: i16,
+pub struct RopePartPosition {
+    pub horizontal_diff: i16,
+    pub vertical_diff: i16,
 }
 
 impl RopePartPosition {
@@ -40,7 +43,7 @@ impl RopePartPosition {
 
 /// A rope. The positions are of the head towards the tail. Except for the tail,
 /// all positions are relative to their child.
-struct Rope<const LENGTH: usize>([RopePartPosition; LENGTH]);
+pub struct Rope<const LENGTH: usize>([RopePartPosition; LENGTH]);
 
 impl<const LENGTH: usize> Default for Rope<LENGTH> {
     fn default() -> Self {
@@ -50,7 +53,7 @@ impl<const LENGTH: usize> Default for Rope<LENGTH> {
 
 impl<const LENGTH: usize> Rope<LENGTH> {
     /// Move this rope one step in the right direction.
-    fn move_in_direction(&mut self, direction: Direction) {
+    pub fn move_in_direction(&mut self, direction: Direction) {
         // Move the head in the right direction.
         self.0[0].move_in_direction(direction);
         // All other parts should follow
@@ -62,21 +65,21 @@ impl<const LENGTH: usize> Rope<LENGTH> {
         }
     }
 
-    fn tail_position(&self) -> RopePartPosition {
+    pub fn tail_position(&self) -> RopePartPosition {
         self.0[LENGTH - 1]
     }
 }
 
 /// Grid direction.
 #[derive(Copy, Clone, Debug)]
-enum Direction {
+pub enum Direction {
     Up,
     Down,
     Left,
     Right,
 }
 
-fn parse_instructions(s: &str) -> impl Iterator<Item = (Direction, u8)> + '_ {
+pub fn parse_instructions(s: &str) -> impl Iterator<Item = (Direction, u8)> + '_ {
     let mut bytes = s.as_bytes().iter();
     std::iter::from_fn(move || {
         bytes
@@ -92,30 +95,54 @@ fn parse_instructions(s: &str) -> impl Iterator<Item = (Direction, u8)> + '_ {
                 // Skip space
                 bytes.next();
 
-                // Read first digit
+                // Read the digits, up to the line ending (if present)
                 let mut num = bytes.next().unwrap() & 0b1111;
-                if let Some(another_digit) = bytes.next() {
-                    // The next byte is either a new line, then do nothing,
-                    // or another (final) digit.
-                    if *another_digit != b'\n' {
-                        num *= 10;
-                        num += another_digit & 0b1111;
-                        // Skip line ending (if present)
-                        bytes.next();
-                    }
+                for digit in bytes.by_ref().take_while(|b| **b != b'\n') {
+                    num = num * 10 + (digit & 0b1111);
                 }
                 (direction, num)
             })
     })
 }
 
+pub fn try_parse_instructions(s: &str) -> Result<Vec<(Direction, u8)>, ParseError> {
+    let mut scanner = Scanner::new(s);
+    let mut instructions = Vec::new();
+    // The position of the head, which the tail stays close to.
+    let (mut x, mut y) = (0i32, 0i32);
+    while !scanner.is_at_end() {
+        let (direction, coordinate, step) = match scanner.one_of(b"UDLR", "U, D, L or R")? {
+            b'U' => (Direction::Up, &mut y, 1),
+            b'D' => (Direction::Down, &mut y, -1),
+            b'L' => (Direction::Left, &mut x, -1),
+            _ => (Direction::Right, &mut x, 1),
+        };
+        scanner.tag(" ")?;
+        let start = scanner.position();
+        let distance: u8 = scanner.number()?;
+        *coordinate += step * i32::from(distance);
+        if i16::try_from(*coordinate).is_err() {
+            return Err(scanner.invalid_at(start, "the rope moves too far"));
+        }
+        scanner.line_end()?;
+        instructions.push((direction, distance));
+    }
+    Ok(instructions)
+}
+
 pub fn simulate_rope<const LENGTH: usize>(s: &str) -> usize {
+    simulate_instructions::<LENGTH>(parse_instructions(s))
+}
+
+fn simulate_instructions<const LENGTH: usize>(
+    instructions: impl Iterator<Item = (Direction, u8)>,
+) -> usize {
     // If the rope is at least two long, we don't have to store the starting
     // position since the tail won't move on the first iteration.
     debug_assert!(LENGTH >= 2);
     let mut positions = HashSet::new();
     let mut rope = Rope::<LENGTH>::default();
-    for (direction, distance) in parse_instructions(s) {
+    for (direction, distance) in instructions {
         for _ in 0..distance {
             rope.move_in_direction(direction);
             positions.insert(rope.tail_position());
@@ -124,12 +151,65 @@ pub fn simulate_rope<const LENGTH: usize>(s: &str) -> usize {
     positions.len()
 }
 
+pub fn parse(s: &str) -> Vec<(Direction, u8)> {
+    parse_instructions(s).collect()
+}
+
+pub fn solve_part_1(instructions: &[(Direction, u8)]) -> usize {
+    simulate_instructions::<2>(instructions.iter().copied())
+}
+
+pub fn solve_part_2(instructions: &[(Direction, u8)]) -> usize {
+    simulate_instructions::<10>(instructions.iter().copied())
+}
+
 pub fn part_1(s: &str) -> usize {
-    simulate_rope::<2>(s)
+    let s = &normalize_input(s);
+    solve_part_1(&parse(s))
 }
 
 pub fn part_2(s: &str) -> usize {
-    simulate_rope::<10>(s)
+    let s = &normalize_input(s);
+    solve_part_2(&parse(s))
+}
+
+pub fn try_part_1(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    Ok(solve_part_1(&try_parse_instructions(s)?))
+}
+
+pub fn try_part_2(s: &str) -> Result<usize, ParseError> {
+    let s = &normalize_input(s);
+    Ok(solve_part_2(&try_parse_instructions(s)?))
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+    type Parsed<'a> = Vec<(Direction, u8)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Vec<(Direction, u8)> {
+        parse(input)
+    }
+
+    fn part_1(instructions: &Vec<(Direction, u8)>) -> usize {
+        solve_part_1(instructions)
+    }
+
+    fn part_2(instructions: &Vec<(Direction, u8)>) -> usize {
+        solve_part_2(instructions)
+    }
+
+    fn try_part_1(input: &str) -> Result<usize, ParseError> {
+        try_part_1(input)
+    }
+
+    fn try_part_2(input: &str) -> Result<usize, ParseError> {
+        try_part_2(input)
+    }
 }
 
 #[test]
@@ -182,3 +262,46 @@ fn test_part_2() {
     let input = include_str!("../input/2022/day9.txt");
     assert_eq!(part_2(input), 2734);
 }
+
+#[test]
+fn test_recorded_answers() {
+    crate::verify::assert_recorded_answers(9);
+}
+
+#[test]
+fn test_try_parts() {
+    let input = include_str!("../input/2022/day9.txt");
+    assert_eq!(try_part_1(input), Ok(part_1(input)));
+    assert_eq!(try_part_2(input), Ok(part_2(input)));
+    assert_eq!(try_part_1("R 4\nU 100\n"), Ok(103));
+    assert_eq!(part_1("R 4\nU 100\n"), 103);
+    assert_eq!(
+        try_part_1(&"L 255\n".repeat(129)).unwrap_err().to_string(),
+        "line 129, column 3: the rope moves too far"
+    );
+    assert_eq!(
+        try_part_1("R 4\nX 4\n").unwrap_err().to_string(),
+        "line 2, column 1: expected U, D, L or R, found 'X'"
+    );
+    assert_eq!(
+        try_part_2("R 4\nU\n").unwrap_err().to_string(),
+        "line 2, column 2: expected \" \", found '\\n'"
+    );
+}
+
+#[test]
+fn test_crlf_example() {
+    let input = crate::util::windows_copy(
+        "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2",
+    );
+    assert_eq!(part_1(&input), 13);
+    assert_eq!(part_2(&input), 1);
+    assert_eq!(try_part_1(&input), Ok(13));
+}
@@ -0,0 +1,489 @@
+//! Seeded generators of random puzzle input. Every generator produces input
+//! in the puzzle's format that the fast path of its day can solve, at a size
+//! that can be chosen to see how a solution scales.
+
+use std::fmt::Write;
+
+use crate::{day11::MAX_MONKEYS, day5::MAX_STACK_HEIGHT};
+
+/// A small xorshift generator, so that generating input doesn't need a
+/// dependency.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // The state must not be zero, or it would stay zero.
+        Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// A number in `low..=high`.
+    pub fn between(&mut self, low: i64, high: i64) -> i64 {
+        low + self.below(high.abs_diff(low) + 1) as i64
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len() as u64) as usize]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i as u64 + 1) as usize);
+        }
+    }
+}
+
+/// Generate input for a day from a seed. What `size` counts is documented on
+/// the generator of each day.
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    let rng = &mut Rng::new(seed);
+    Some(match day {
+        1 => day1(rng, size),
+        2 => day2(rng, size),
+        3 => day3(rng, size),
+        4 => day4(rng, size),
+        5 => day5(rng, size),
+        6 => day6(rng, size),
+        7 => day7(rng, size),
+        8 => day8(rng, size),
+        9 => day9(rng, size),
+        10 => day10(rng, size),
+        11 => day11(rng, size),
+        12 => day12(rng, size),
+        13 => day13(rng, size),
+        _ => return None,
+    })
+}
+
+/// The inventories of `size` elves, with at least three elves.
+pub fn day1(rng: &mut Rng, size: usize) -> String {
+    let mut s = String::new();
+    for elf in 0..size.max(3) {
+        if elf > 0 {
+            s.push('\n');
+        }
+        for _ in 0..rng.between(1, 10) {
+            writeln!(s, "{}", rng.between(1, 99_999)).unwrap();
+        }
+    }
+    s
+}
+
+/// A strategy guide of `size` rounds.
+pub fn day2(rng: &mut Rng, size: usize) -> String {
+    let mut s = String::new();
+    for _ in 0..size.max(1) {
+        let opponent = char::from(rng.pick(b"ABC"));
+        let response = char::from(rng.pick(b"XYZ"));
+        writeln!(s, "{opponent} {response}").unwrap();
+    }
+    s
+}
+
+/// `size` groups of three rucksacks.
+pub fn day3(rng: &mut Rng, size: usize) -> String {
+    let mut letters: Vec<u8> = (b'a'..=b'z').chain(b'A'..=b'Z').collect();
+    let mut s = String::new();
+    for _ in 0..size.max(1) {
+        rng.shuffle(&mut letters);
+        let (&badge, rest) = letters.split_first().unwrap();
+        // Every rucksack draws from its own letters, and every compartment
+        // from its own half of those, so that the only letters in common are
+        // the badge and the misplaced item.
+        for own in rest.chunks(rest.len() / 3) {
+            let (first, second) = own.split_at(own.len() / 2);
+            let misplaced = if rng.below(4) == 0 {
+                badge
+            } else {
+                rng.pick(own)
+            };
+            let len = rng.below(12) as usize;
+            let mut first_half = vec![misplaced, badge];
+            first_half.extend((0..len).map(|_| rng.pick(first)));
+            let mut second_half = vec![misplaced];
+            second_half.extend((0..=len).map(|_| rng.pick(second)));
+            rng.shuffle(&mut first_half);
+            rng.shuffle(&mut second_half);
+            s.extend(first_half.into_iter().chain(second_half).map(char::from));
+            s.push('\n');
+        }
+    }
+    s
+}
+
+/// A list of `size` pairs of section assignments.
+pub fn day4(rng: &mut Rng, size: usize) -> String {
+    let mut s = String::new();
+    for _ in 0..size.max(1) {
+        let a = rng.between(1, 99);
+        let b = rng.between(a, 99);
+        let c = rng.between(1, 99);
+        let d = rng.between(c, 99);
+        writeln!(s, "{a}-{b},{c}-{d}").unwrap();
+    }
+    s
+}
+
+/// A drawing of up to nine stacks, followed by `size` moves. There are never
+/// more crates than fit in a single stack.
+pub fn day5(rng: &mut Rng, size: usize) -> String {
+    let stacks = rng.between(2, 9) as usize;
+    let mut heights = vec![0i64; stacks];
+    for _ in 0..rng.between(1, MAX_STACK_HEIGHT as i64) {
+        heights[rng.below(stacks as u64) as usize] += 1;
+    }
+    let mut s = String::new();
+    for row in (0..*heights.iter().max().unwrap()).rev() {
+        for (stack, height) in heights.iter().enumerate() {
+            if stack > 0 {
+                s.push(' ');
+            }
+            if row < *height {
+                write!(s, "[{}]", char::from(b'A' + rng.below(26) as u8)).unwrap();
+            } else {
+                s.push_str("   ");
+            }
+        }
+        s.push('\n');
+    }
+    for stack in 1..=stacks {
+        if stack > 1 {
+            s.push(' ');
+        }
+        write!(s, " {stack} ").unwrap();
+    }
+    s.push_str("\n\n");
+    for _ in 0..size.max(1) {
+        let non_empty: Vec<_> = (0..stacks).filter(|stack| heights[*stack] > 0).collect();
+        let from = rng.pick(&non_empty);
+        let to = (from + 1 + rng.below(stacks as u64 - 1) as usize) % stacks;
+        let count = rng.between(1, heights[from]);
+        heights[from] -= count;
+        heights[to] += count;
+        writeln!(s, "move {count} from {} to {}", from + 1, to + 1).unwrap();
+    }
+    s
+}
+
+/// A signal of `size` letters, followed by a start-of-message marker.
+pub fn day6(rng: &mut Rng, size: usize) -> String {
+    // With only 13 different letters, no start-of-message marker can be
+    // found before the end.
+    let mut s: String = (0..size)
+        .map(|_| char::from(b'a' + rng.below(13) as u8))
+        .collect();
+    let mut letters: Vec<u8> = (b'a'..=b'z').collect();
+    rng.shuffle(&mut letters);
+    s.extend(letters[..14].iter().copied().map(char::from));
+    s.push('\n');
+    s
+}
+
+/// A name made of lowercase letters, unique for every number.
+fn name(mut number: usize) -> String {
+    let mut name = String::new();
+    loop {
+        name.push(char::from(b'a' + (number % 26) as u8));
+        number /= 26;
+        if number == 0 {
+            return name;
+        }
+        number -= 1;
+    }
+}
+
+/// A terminal output exploring `size` directories. About half of the
+/// directories are nested in the one created just before, so the tree gets
+/// deep. The disk is between 41,000,000 and 70,000,000 full, for inputs of up
+/// to a million files.
+pub fn day7(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let mut subdirectories = vec![Vec::new(); size];
+    for directory in 1..size {
+        let parent = if rng.below(2) == 0 {
+            directory - 1
+        } else {
+            rng.below(directory as u64) as usize
+        };
+        subdirectories[parent].push(directory);
+    }
+    let weights: Vec<Vec<u64>> = (0..size)
+        .map(|directory| {
+            let files = rng.below(5) + u64::from(directory == 0);
+            (0..files).map(|_| rng.between(1, 1000) as u64).collect()
+        })
+        .collect();
+    let total_weight: u64 = weights.iter().flatten().sum();
+    let disk_used = rng.between(41_000_000, 69_000_000) as u64;
+
+    let mut s = String::from("$ cd /\n");
+    let mut list = |s: &mut String, directory: usize| {
+        let mut lines: Vec<_> = subdirectories[directory]
+            .iter()
+            .map(|subdirectory| format!("dir {}", name(*subdirectory)))
+            .collect();
+        for (file, weight) in weights[directory].iter().enumerate() {
+            let file_size = (weight * disk_used / total_weight).max(1);
+            let extension = rng.pick(&["", ".txt", ".dat", ".log"]);
+            lines.push(format!("{file_size} {}{extension}", name(file)));
+        }
+        rng.shuffle(&mut lines);
+        s.push_str("$ ls\n");
+        for line in lines {
+            writeln!(s, "{line}").unwrap();
+        }
+    };
+    list(&mut s, 0);
+    // The directories being explored, with how many of their subdirectories
+    // have been visited.
+    let mut path = vec![(0, 0)];
+    while let Some(&(directory, visited)) = path.last() {
+        if let Some(&subdirectory) = subdirectories[directory].get(visited) {
+            path.last_mut().unwrap().1 += 1;
+            writeln!(s, "$ cd {}", name(subdirectory)).unwrap();
+            list(&mut s, subdirectory);
+            path.push((subdirectory, 0));
+        } else {
+            path.pop();
+            if !path.is_empty() {
+                s.push_str("$ cd ..\n");
+            }
+        }
+    }
+    s
+}
+
+/// A `size` by `size` grid of trees.
+pub fn day8(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let mut s = String::with_capacity(size * (size + 1));
+    for _ in 0..size {
+        s.extend((0..size).map(|_| char::from(b'0' + rng.below(10) as u8)));
+        s.push('\n');
+    }
+    s
+}
+
+/// A list of `size` motions. The head is kept within a thousand steps of
+/// the start.
+pub fn day9(rng: &mut Rng, size: usize) -> String {
+    let mut s = String::new();
+    let (mut x, mut y) = (0, 0);
+    for _ in 0..size.max(1) {
+        let distance = rng.between(1, 20);
+        let direction = match rng.pick(b"UDLR") {
+            b'U' | b'D' if y > 1000 => b'D',
+            b'U' | b'D' if y < -1000 => b'U',
+            b'L' | b'R' if x > 1000 => b'L',
+            b'L' | b'R' if x < -1000 => b'R',
+            direction => direction,
+        };
+        match direction {
+            b'U' => y += distance,
+            b'D' => y -= distance,
+            b'L' => x -= distance,
+            _ => x += distance,
+        }
+        writeln!(s, "{} {distance}", char::from(direction)).unwrap();
+    }
+    s
+}
+
+/// A program of `size` instructions. The register stays within 40 of zero.
+pub fn day10(rng: &mut Rng, size: usize) -> String {
+    let mut s = String::new();
+    let mut x = 1;
+    for _ in 0..size.max(1) {
+        if rng.below(3) == 0 {
+            s.push_str("noop\n");
+        } else {
+            let value = rng.between((-40 - x).max(-20), (40 - x).min(20));
+            x += value;
+            writeln!(s, "addx {value}").unwrap();
+        }
+    }
+    s
+}
+
+/// Notes on up to `MAX_MONKEYS` monkeys, holding `size` items between them.
+/// There are at least as many items as monkeys, and at most `MAX_ITEMS`.
+///
+/// Monkeys never square the worry level, and multiply it by at most three, so
+/// that the worry levels of part 1 cannot overflow. The tests are distinct
+/// primes, so that part 2 cannot overflow either.
+pub fn day11(rng: &mut Rng, size: usize) -> String {
+    let monkeys = rng.between(2, MAX_MONKEYS as i64) as usize;
+    let mut items = vec![1; monkeys];
+    for _ in monkeys..size.min(crate::day11::MAX_ITEMS) {
+        items[rng.below(monkeys as u64) as usize] += 1;
+    }
+    let mut primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29];
+    rng.shuffle(&mut primes);
+    let mut s = String::new();
+    for (monkey, items) in items.into_iter().enumerate() {
+        if monkey > 0 {
+            s.push('\n');
+        }
+        let items: Vec<_> = (0..items)
+            .map(|_| rng.between(10, 99).to_string())
+            .collect();
+        let operation = if rng.below(2) == 0 {
+            format!("* {}", rng.between(2, 3))
+        } else {
+            format!("+ {}", rng.between(1, 9))
+        };
+        // Two different monkeys, neither of which is this one.
+        let if_true = (monkey + 1 + rng.below(monkeys as u64 - 1) as usize) % monkeys;
+        let mut if_false = (monkey + 1 + rng.below(monkeys as u64 - 1) as usize) % monkeys;
+        if monkeys > 2 {
+            while if_false == if_true {
+                if_false = (monkey + 1 + rng.below(monkeys as u64 - 1) as usize) % monkeys;
+            }
+        }
+        writeln!(
+            s,
+            "Monkey {monkey}:
+  Starting items: {}
+  Operation: new = old {operation}
+  Test: divisible by {}
+    If true: throw to monkey {if_true}
+    If false: throw to monkey {if_false}",
+            items.join(", "),
+            primes[monkey],
+        )
+        .unwrap();
+    }
+    s
+}
+
+/// A `size` by `size` heightmap, at least 14 squares wide so that it can rise
+/// from `a` to `z`. Neighbouring squares differ by at most one, so the goal
+/// can be reached from everywhere.
+pub fn day12(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(14);
+    // Random terrain, in which every square is within one of the squares to
+    // the left and above. Those two are within two of each other, as both are
+    // within one of the square diagonally above.
+    let mut terrain = vec![0; size * size];
+    for y in 0..size {
+        for x in 0..size {
+            let (mut low, mut high) = (i64::MIN, i64::MAX);
+            if x > 0 {
+                low = low.max(terrain[x - 1 + y * size] - 1);
+                high = high.min(terrain[x - 1 + y * size] + 1);
+            }
+            if y > 0 {
+                low = low.max(terrain[x + (y - 1) * size] - 1);
+                high = high.min(terrain[x + (y - 1) * size] + 1);
+            }
+            if x + y > 0 {
+                terrain[x + y * size] = rng.between(low, high);
+            }
+        }
+    }
+    // Limit the terrain by a slope rising from the start in the top left,
+    // and raise it so that the goal in the bottom right is at `z`. Both keep
+    // neighbouring squares within one.
+    let raise = 25 - terrain[size * size - 1];
+    let mut s = String::with_capacity(size * (size + 1));
+    for (index, height) in terrain.into_iter().enumerate() {
+        let slope = (index % size + index / size) as i64;
+        let height = (height + raise).min(slope).clamp(0, 25);
+        s.push(match index {
+            0 => 'S',
+            _ if index == size * size - 1 => 'E',
+            _ => char::from(b'a' + height as u8),
+        });
+        if index % size == size - 1 {
+            s.push('\n');
+        }
+    }
+    s
+}
+
+/// Write a random packet, nested at most `depth` deep.
+fn packet(rng: &mut Rng, s: &mut String, depth: usize) {
+    s.push('[');
+    for item in 0..rng.below(5) {
+        if item > 0 {
+            s.push(',');
+        }
+        if depth == 0 || rng.below(2) == 0 {
+            write!(s, "{}", rng.below(11)).unwrap();
+        } else {
+            packet(rng, s, depth - 1);
+        }
+    }
+    s.push(']');
+}
+
+/// A list of `size` pairs of packets.
+pub fn day13(rng: &mut Rng, size: usize) -> String {
+    let mut s = String::new();
+    for pair in 0..size.max(1) {
+        if pair > 0 {
+            s.push('\n');
+        }
+        for _ in 0..2 {
+            packet(rng, &mut s, 4);
+            s.push('\n');
+        }
+    }
+    s
+}
+
+#[test]
+fn test_generated_input_is_valid() {
+    macro_rules! check {
+        ($($day:ident),*) => {$(
+            let number: u8 = stringify!($day)[3..].parse().unwrap();
+            for seed in 0..10 {
+                for size in [1, 10, 100] {
+                    let input = generate(number, seed, size).unwrap();
+                    assert_eq!(
+                        crate::$day::try_part_1(&input).map(|answer| answer.to_string()),
+                        Ok(crate::$day::part_1(&input).to_string()),
+                        "part 1 of {} with seed {seed} and size {size}:\n{input}",
+                        stringify!($day),
+                    );
+                    assert_eq!(
+                        crate::$day::try_part_2(&input).map(|answer| answer.to_string()),
+                        Ok(crate::$day::part_2(&input).to_string()),
+                        "part 2 of {} with seed {seed} and size {size}:\n{input}",
+                        stringify!($day),
+                    );
+                }
+            }
+        )*};
+    }
+    check!(day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13);
+}
+
+#[test]
+fn test_generate_is_deterministic() {
+    for day in 1..=13 {
+        assert_eq!(generate(day, 7, 50), generate(day, 7, 50));
+        assert_ne!(generate(day, 7, 50), generate(day, 8, 50));
+    }
+    assert_eq!(generate(14, 7, 50), None);
+}
+
+#[test]
+fn test_generate_scales() {
+    assert_eq!(day2(&mut Rng::new(1), 1000).lines().count(), 1000);
+    assert_eq!(day3(&mut Rng::new(1), 1000).lines().count(), 3000);
+    assert_eq!(day8(&mut Rng::new(1), 1000).len(), 1000 * 1001);
+    assert_eq!(day6(&mut Rng::new(1), 1000).len(), 1015);
+    assert!(crate::day6::part_2(&day6(&mut Rng::new(1), 1000)) > 1000);
+}
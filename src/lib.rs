@@ -0,0 +1,24 @@
+#![cfg_attr(feature = "nightly", feature(iter_array_chunks))]
+#![cfg_attr(feature = "nightly", feature(iter_advance_by))]
+
+pub mod bench;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod generate;
+pub mod ocr;
+pub mod reference;
+pub mod run;
+pub mod solution;
+pub mod util;
+pub mod verify;
@@ -1,23 +1,9 @@
-#![feature(iter_array_chunks)]
-#![feature(iter_advance_by)]
-#![feature(array_windows)]
-#![feature(slice_internals)]
-
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-pub(crate) mod util;
+use advent_of_code_2022::{
+    day1, day10, day11, day12, day13, day2, day3, day4, day5, day6, day7, day8, day9,
+};
 
+// The runner times each part as a whole, normalising the input as it goes.
+// `aoc bench-all` reports the parse and solve phases separately.
 aoc_main::main! {
     year 2022;
     day1 => part_1, part_2;
@@ -32,4 +18,5 @@ aoc_main::main! {
     day10 => part_1, part_2;
     day11 => part_1, part_2;
     day12 => part_1, part_2;
+    day13 => part_1, part_2;
 }
@@ -0,0 +1,89 @@
+//! Reading the letters that some puzzles draw on a screen of `#` and `.`.
+
+/// The letters that are known, which are 4 pixels wide and 6 high. The rows
+/// of each letter are joined together.
+const LETTERS: [(char, &str); 18] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Y', "#...#....#.#..#...#...#."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+const WIDTH: usize = 4;
+const HEIGHT: usize = 6;
+
+/// Read the letters on a screen, which has rows of equal length with an empty
+/// column after every letter. Returns `None` if the screen does not consist
+/// of known letters only.
+pub fn read_letters(screen: &str) -> Option<String> {
+    let rows: Vec<&[u8]> = screen.lines().map(str::as_bytes).collect();
+    let width = rows.first()?.len();
+    if rows.len() != HEIGHT || width == 0 || rows.iter().any(|row| row.len() != width) {
+        return None;
+    }
+    (0..width)
+        .step_by(WIDTH + 1)
+        .map(|left| {
+            let mut letter = String::with_capacity(WIDTH * HEIGHT);
+            for row in &rows {
+                letter.push_str(std::str::from_utf8(row.get(left..left + WIDTH)?).ok()?);
+                if row
+                    .get(left + WIDTH)
+                    .is_some_and(|&spacing| spacing != b'.')
+                {
+                    return None;
+                }
+            }
+            LETTERS
+                .iter()
+                .find(|(_, pixels)| *pixels == letter)
+                .map(|(c, _)| *c)
+        })
+        .collect()
+}
+
+#[test]
+fn test_read_letters() {
+    let screen = "\
+###..####..##..###..#..#.###..####.###..
+#..#....#.#..#.#..#.#.#..#..#.#....#..#.
+#..#...#..#....#..#.##...#..#.###..###..
+###...#...#.##.###..#.#..###..#....#..#.
+#....#....#..#.#....#.#..#....#....#..#.
+#....####..###.#....#..#.#....####.###..
+";
+    assert_eq!(read_letters(screen).as_deref(), Some("PZGPKPEB"));
+    // Without the empty column after the last letter
+    let trimmed: String = screen
+        .lines()
+        .map(|row| format!("{}\n", &row[..row.len() - 1]))
+        .collect();
+    assert_eq!(read_letters(&trimmed).as_deref(), Some("PZGPKPEB"));
+    for (c, pixels) in LETTERS {
+        let rows: Vec<_> = pixels.as_bytes().chunks(WIDTH).collect();
+        let screen = rows
+            .iter()
+            .map(|row| std::str::from_utf8(row).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(read_letters(&screen), Some(c.to_string()));
+    }
+    assert_eq!(read_letters(&screen.replacen('#', ".", 1)), None);
+    assert_eq!(read_letters(&screen[41..]), None);
+    assert_eq!(read_letters(""), None);
+    assert_eq!(read_letters("##\n##\n##\n##\n##\n##\n"), None);
+}
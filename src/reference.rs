@@ -0,0 +1,534 @@
+//! Straightforward solutions of every day, written for clarity rather than
+//! speed. They serve as the reference that the optimised solutions are tested
+//! against, on generated input. They expect valid, normalised input.
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashSet, VecDeque},
+};
+
+/// Solve both parts of a day, formatting the answers like the optimised
+/// solutions do.
+pub fn solve(day: u8, input: &str) -> Option<[String; 2]> {
+    Some(match day {
+        1 => day1(input),
+        2 => day2(input),
+        3 => day3(input),
+        4 => day4(input),
+        5 => day5(input),
+        6 => day6(input),
+        7 => day7(input),
+        8 => day8(input),
+        9 => day9(input),
+        10 => day10(input),
+        11 => day11(input),
+        12 => day12(input),
+        13 => day13(input),
+        _ => return None,
+    })
+}
+
+fn answers(part_1: impl ToString, part_2: impl ToString) -> [String; 2] {
+    [part_1.to_string(), part_2.to_string()]
+}
+
+pub fn day1(input: &str) -> [String; 2] {
+    let mut totals: Vec<usize> = input
+        .split("\n\n")
+        .map(|elf| elf.lines().map(|line| line.parse::<usize>().unwrap()).sum())
+        .collect();
+    totals.sort_unstable_by(|a, b| b.cmp(a));
+    answers(totals[0], totals[..3].iter().sum::<usize>())
+}
+
+pub fn day2(input: &str) -> [String; 2] {
+    // Rock, paper and scissors are 0, 1 and 2, so that every shape beats the
+    // one before it.
+    let score = |opponent: u8, response: u8| {
+        let outcome = if response == opponent {
+            3
+        } else if response == (opponent + 1) % 3 {
+            6
+        } else {
+            0
+        };
+        usize::from(response) + 1 + outcome
+    };
+    let (mut part_1, mut part_2) = (0, 0);
+    for line in input.lines() {
+        let bytes = line.as_bytes();
+        let opponent = bytes[0] - b'A';
+        let column = bytes[2] - b'X';
+        part_1 += score(opponent, column);
+        let response = match column {
+            0 => (opponent + 2) % 3,
+            1 => opponent,
+            _ => (opponent + 1) % 3,
+        };
+        part_2 += score(opponent, response);
+    }
+    answers(part_1, part_2)
+}
+
+fn priority(item: char) -> usize {
+    match item {
+        'a'..='z' => item as usize - 'a' as usize + 1,
+        _ => item as usize - 'A' as usize + 27,
+    }
+}
+
+/// The one item that all of the lists have in common.
+fn common_item(lists: &[&str]) -> char {
+    let mut common: HashSet<char> = lists[0].chars().collect();
+    for list in &lists[1..] {
+        let items: HashSet<char> = list.chars().collect();
+        common.retain(|item| items.contains(item));
+    }
+    assert_eq!(
+        common.len(),
+        1,
+        "{lists:?} have more than one item in common"
+    );
+    common.into_iter().next().unwrap()
+}
+
+pub fn day3(input: &str) -> [String; 2] {
+    let rucksacks: Vec<&str> = input.lines().collect();
+    let part_1: usize = rucksacks
+        .iter()
+        .map(|rucksack| {
+            let (first, second) = rucksack.split_at(rucksack.len() / 2);
+            priority(common_item(&[first, second]))
+        })
+        .sum();
+    let part_2: usize = rucksacks
+        .chunks(3)
+        .map(|group| priority(common_item(group)))
+        .sum();
+    answers(part_1, part_2)
+}
+
+pub fn day4(input: &str) -> [String; 2] {
+    let (mut part_1, mut part_2) = (0, 0);
+    for line in input.lines() {
+        let numbers: Vec<u32> = line
+            .split([',', '-'])
+            .map(|number| number.parse().unwrap())
+            .collect();
+        let [a, b, c, d] = numbers[..] else {
+            panic!("not a pair of ranges: {line}");
+        };
+        if (a <= c && d <= b) || (c <= a && b <= d) {
+            part_1 += 1;
+        }
+        if a <= d && c <= b {
+            part_2 += 1;
+        }
+    }
+    answers(part_1, part_2)
+}
+
+/// Rearrange the crates, and list the crate on top of each stack. Empty
+/// stacks are shown as a space.
+fn rearrange_crates(input: &str, one_at_a_time: bool) -> String {
+    let (drawing, moves) = input.split_once("\n\n").unwrap();
+    let mut rows: Vec<&str> = drawing.lines().collect();
+    let numbers = rows.pop().unwrap();
+    let mut stacks = vec![Vec::new(); numbers.split_whitespace().count()];
+    for row in rows.iter().rev() {
+        for (stack, crates) in stacks.iter_mut().enumerate() {
+            match row.chars().nth(1 + 4 * stack) {
+                Some(letter) if letter.is_ascii_uppercase() => crates.push(letter),
+                _ => {}
+            }
+        }
+    }
+    for line in moves.lines() {
+        let words: Vec<&str> = line.split(' ').collect();
+        let count: usize = words[1].parse().unwrap();
+        let from = words[3].parse::<usize>().unwrap() - 1;
+        let to = words[5].parse::<usize>().unwrap() - 1;
+        let remaining = stacks[from].len() - count;
+        let mut moved = stacks[from].split_off(remaining);
+        if one_at_a_time {
+            moved.reverse();
+        }
+        stacks[to].extend(moved);
+    }
+    stacks
+        .iter()
+        .map(|crates| crates.last().copied().unwrap_or(' '))
+        .collect()
+}
+
+pub fn day5(input: &str) -> [String; 2] {
+    answers(
+        rearrange_crates(input, true),
+        rearrange_crates(input, false),
+    )
+}
+
+/// The number of characters read when the last `length` were all different.
+fn marker(signal: &str, length: usize) -> usize {
+    let signal: Vec<char> = signal.trim_end().chars().collect();
+    (length..=signal.len())
+        .find(|end| {
+            let window: HashSet<_> = signal[end - length..*end].iter().collect();
+            window.len() == length
+        })
+        .unwrap()
+}
+
+pub fn day6(input: &str) -> [String; 2] {
+    answers(marker(input, 4), marker(input, 14))
+}
+
+pub fn day7(input: &str) -> [String; 2] {
+    // The total size of every directory, by its path.
+    let mut sizes: BTreeMap<Vec<&str>, usize> = BTreeMap::new();
+    let mut path = Vec::new();
+    for line in input.lines() {
+        let words: Vec<&str> = line.split(' ').collect();
+        match words[..] {
+            ["$", "cd", "/"] => path.clear(),
+            ["$", "cd", ".."] => {
+                path.pop();
+            }
+            ["$", "cd", directory] => path.push(directory),
+            ["$", "ls"] => {
+                sizes.entry(path.clone()).or_default();
+            }
+            ["dir", _] => {}
+            [size, _] => {
+                let size: usize = size.parse().unwrap();
+                for depth in 0..=path.len() {
+                    *sizes.entry(path[..depth].to_vec()).or_default() += size;
+                }
+            }
+            _ => panic!("unexpected line: {line}"),
+        }
+    }
+    let part_1: usize = sizes.values().filter(|size| **size <= 100_000).sum();
+    let needed = (sizes[&Vec::new()] + 30_000_000).saturating_sub(70_000_000);
+    let part_2 = sizes
+        .values()
+        .filter(|size| **size >= needed)
+        .min()
+        .unwrap();
+    answers(part_1, part_2)
+}
+
+pub fn day8(input: &str) -> [String; 2] {
+    let grid: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+    let (height, width) = (grid.len(), grid[0].len());
+    let (mut visible, mut best_score) = (0, 0);
+    for y in 0..height {
+        for x in 0..width {
+            let tree = grid[y][x];
+            let lines_of_sight: [Vec<u8>; 4] = [
+                (0..x).rev().map(|x| grid[y][x]).collect(),
+                (x + 1..width).map(|x| grid[y][x]).collect(),
+                (0..y).rev().map(|y| grid[y][x]).collect(),
+                (y + 1..height).map(|y| grid[y][x]).collect(),
+            ];
+            if lines_of_sight
+                .iter()
+                .any(|trees| trees.iter().all(|other| *other < tree))
+            {
+                visible += 1;
+            }
+            let score: usize = lines_of_sight
+                .iter()
+                .map(
+                    |trees| match trees.iter().position(|other| *other >= tree) {
+                        Some(blocked_by) => blocked_by + 1,
+                        None => trees.len(),
+                    },
+                )
+                .product();
+            best_score = best_score.max(score);
+        }
+    }
+    answers(visible, best_score)
+}
+
+/// The number of positions the tail of a rope of `knots` knots visits.
+fn tail_positions(input: &str, knots: usize) -> usize {
+    let mut rope = vec![(0i32, 0i32); knots];
+    let mut visited = HashSet::from([(0, 0)]);
+    for line in input.lines() {
+        let (direction, distance) = line.split_once(' ').unwrap();
+        let (dx, dy) = match direction {
+            "U" => (0, 1),
+            "D" => (0, -1),
+            "L" => (-1, 0),
+            _ => (1, 0),
+        };
+        for _ in 0..distance.parse::<usize>().unwrap() {
+            rope[0].0 += dx;
+            rope[0].1 += dy;
+            for knot in 1..knots {
+                let (x, y) = rope[knot - 1];
+                let knot = &mut rope[knot];
+                if (x - knot.0).abs() > 1 || (y - knot.1).abs() > 1 {
+                    knot.0 += (x - knot.0).signum();
+                    knot.1 += (y - knot.1).signum();
+                }
+            }
+            visited.insert(rope[knots - 1]);
+        }
+    }
+    visited.len()
+}
+
+pub fn day9(input: &str) -> [String; 2] {
+    answers(tail_positions(input, 2), tail_positions(input, 10))
+}
+
+pub fn day10(input: &str) -> [String; 2] {
+    // The value of the register during every cycle. The CPU stops one cycle
+    // after the last instruction, and the pixels after that stay dark.
+    let mut during = Vec::new();
+    let mut x = 1;
+    for line in input.lines() {
+        during.push(x);
+        if let Some(value) = line.strip_prefix("addx ") {
+            during.push(x);
+            x += value.parse::<i32>().unwrap();
+        }
+    }
+    during.push(x);
+    let signal_strength: i32 = [20, 60, 100, 140, 180, 220]
+        .into_iter()
+        .filter_map(|cycle| Some(cycle * during.get(cycle as usize - 1)?))
+        .sum();
+    let mut screen = String::new();
+    for row in 0..6 {
+        for column in 0..40 {
+            let lit = during
+                .get(row * 40 + column)
+                .is_some_and(|x| (x - column as i32).abs() <= 1);
+            screen.push(if lit { '#' } else { '.' });
+        }
+        screen.push('\n');
+    }
+    answers(signal_strength, screen)
+}
+
+struct Monkey {
+    items: VecDeque<u64>,
+    operation: Box<dyn Fn(u64) -> u64>,
+    divisor: u64,
+    if_true: usize,
+    if_false: usize,
+}
+
+fn parse_monkey(notes: &str) -> Monkey {
+    let lines: Vec<&str> = notes.lines().map(str::trim).collect();
+    let last_number = |line: &str| line.rsplit(' ').next().unwrap().parse().unwrap();
+    let items = lines[1]
+        .strip_prefix("Starting items: ")
+        .unwrap()
+        .split(", ")
+        .map(|item| item.parse().unwrap())
+        .collect();
+    let operation = lines[2].strip_prefix("Operation: new = old ").unwrap();
+    let operation: Box<dyn Fn(u64) -> u64> = match operation.split_once(' ').unwrap() {
+        ("*", "old") => Box::new(|old| old * old),
+        ("*", value) => {
+            let value: u64 = value.parse().unwrap();
+            Box::new(move |old| old * value)
+        }
+        (_, value) => {
+            let value: u64 = value.parse().unwrap();
+            Box::new(move |old| old + value)
+        }
+    };
+    Monkey {
+        items,
+        operation,
+        divisor: last_number(lines[3]) as u64,
+        if_true: last_number(lines[4]),
+        if_false: last_number(lines[5]),
+    }
+}
+
+/// The product of the two largest numbers of inspections.
+fn monkey_business(input: &str, rounds: usize, relief: bool) -> usize {
+    let mut monkeys: Vec<Monkey> = input.split("\n\n").map(parse_monkey).collect();
+    let modulus: u64 = monkeys.iter().map(|monkey| monkey.divisor).product();
+    let mut inspections = vec![0; monkeys.len()];
+    for _ in 0..rounds {
+        for index in 0..monkeys.len() {
+            while let Some(item) = monkeys[index].items.pop_front() {
+                inspections[index] += 1;
+                let monkey = &monkeys[index];
+                let mut item = (monkey.operation)(item);
+                if relief {
+                    item /= 3;
+                } else {
+                    item %= modulus;
+                }
+                let target = if item.is_multiple_of(monkey.divisor) {
+                    monkey.if_true
+                } else {
+                    monkey.if_false
+                };
+                monkeys[target].items.push_back(item);
+            }
+        }
+    }
+    inspections.sort_unstable_by(|a, b| b.cmp(a));
+    inspections[0] * inspections[1]
+}
+
+pub fn day11(input: &str) -> [String; 2] {
+    answers(
+        monkey_business(input, 20, true),
+        monkey_business(input, 10_000, false),
+    )
+}
+
+/// The fewest steps from any of the squares that `is_start` accepts to the
+/// goal, searching backwards from the goal.
+fn fewest_steps(input: &str, is_start: impl Fn(u8) -> bool) -> usize {
+    let grid: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+    let height = |square: u8| match square {
+        b'S' => b'a',
+        b'E' => b'z',
+        _ => square,
+    };
+    let mut steps = vec![vec![None; grid[0].len()]; grid.len()];
+    let mut queue = VecDeque::new();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, square) in row.iter().enumerate() {
+            if *square == b'E' {
+                steps[y][x] = Some(0);
+                queue.push_back((x, y));
+            }
+        }
+    }
+    while let Some((x, y)) = queue.pop_front() {
+        let here = steps[y][x].unwrap();
+        if is_start(grid[y][x]) {
+            return here;
+        }
+        let neighbours = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+        for (nx, ny) in neighbours {
+            let Some(square) = grid.get(ny).and_then(|row| row.get(nx)) else {
+                continue;
+            };
+            // Walking backwards, so the neighbour may be at most one lower.
+            if height(*square) + 1 >= height(grid[y][x]) && steps[ny][nx].is_none() {
+                steps[ny][nx] = Some(here + 1);
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    panic!("the goal cannot be reached");
+}
+
+pub fn day12(input: &str) -> [String; 2] {
+    answers(
+        fewest_steps(input, |square| square == b'S'),
+        fewest_steps(input, |square| square == b'S' || square == b'a'),
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Packet {
+    Integer(u128),
+    List(Vec<Packet>),
+}
+
+impl Packet {
+    fn parse(s: &str) -> Packet {
+        let (packet, rest) = Packet::parse_prefix(s);
+        assert!(rest.is_empty(), "unexpected {rest:?} after packet");
+        packet
+    }
+
+    /// Parse a packet from the start of `s`, and return the rest.
+    fn parse_prefix(s: &str) -> (Packet, &str) {
+        if let Some(mut rest) = s.strip_prefix('[') {
+            let mut items = Vec::new();
+            if let Some(rest) = rest.strip_prefix(']') {
+                return (Packet::List(items), rest);
+            }
+            loop {
+                let (item, after_item) = Packet::parse_prefix(rest);
+                items.push(item);
+                match after_item.split_at(1) {
+                    (",", after_comma) => rest = after_comma,
+                    (_, after_list) => return (Packet::List(items), after_list),
+                }
+            }
+        } else {
+            let digits = s.find([',', ']']).unwrap_or(s.len());
+            (Packet::Integer(s[..digits].parse().unwrap()), &s[digits..])
+        }
+    }
+}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Integer(a), Packet::Integer(b)) => a.cmp(b),
+            (Packet::List(a), Packet::List(b)) => a.cmp(b),
+            (Packet::Integer(_), Packet::List(_)) => Packet::List(vec![self.clone()]).cmp(other),
+            (Packet::List(_), Packet::Integer(_)) => self.cmp(&Packet::List(vec![other.clone()])),
+        }
+    }
+}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+pub fn day13(input: &str) -> [String; 2] {
+    let packets: Vec<Packet> = input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(Packet::parse)
+        .collect();
+    let part_1: usize = packets
+        .chunks(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0] < pair[1])
+        .map(|(index, _)| index + 1)
+        .sum();
+    // The dividers go after any packets that are equal to them.
+    let [first, second] = [Packet::parse("[[2]]"), Packet::parse("[[6]]")];
+    let before = |divider: &Packet| packets.iter().filter(|packet| *packet < divider).count();
+    let part_2 = (before(&first) + 1) * (before(&second) + 2);
+    answers(part_1, part_2)
+}
+
+#[test]
+fn test_recorded_answers() {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let answers = crate::verify::Answers::load(&root.join("answers/2022.toml")).unwrap();
+    let input_dir = root.join("input/2022");
+    for day in 1..=13 {
+        for input in crate::verify::input_files(&input_dir, day).unwrap() {
+            let text = crate::util::normalize_input(
+                &std::fs::read_to_string(input_dir.join(&input)).unwrap(),
+            )
+            .into_owned();
+            for (part, answer) in (1..=2).zip(solve(day, &text).unwrap()) {
+                assert_eq!(
+                    Some(answer.trim_end()),
+                    answers.get(day, &input, part).map(str::trim_end),
+                    "{input} part {part}"
+                );
+            }
+        }
+    }
+}
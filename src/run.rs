@@ -0,0 +1,168 @@
+use std::{fs, io, path::Path, time::Duration};
+
+use serde::{Serialize, Serializer};
+
+use crate::ocr;
+use crate::solution::Day;
+use crate::util::ParseError;
+use crate::verify;
+
+/// The answer to a part for one input file, and how long it took.
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub day: u8,
+    pub part: u8,
+    /// The path of the input file, including the input directory.
+    pub input: String,
+    pub answer: String,
+    /// How long parsing took. The input is parsed once for both parts.
+    pub parse: Duration,
+    pub duration: Duration,
+}
+
+impl PartResult {
+    /// The letters drawn by an answer that spans multiple lines, such as the
+    /// screen of day 10, if they can be read.
+    pub fn text(&self) -> Option<String> {
+        if self.answer.contains('\n') {
+            ocr::read_letters(&self.answer)
+        } else {
+            None
+        }
+    }
+}
+
+/// A result as it is written in JSON, with durations in nanoseconds.
+#[derive(Serialize)]
+struct PartJson<'a> {
+    day: u8,
+    part: u8,
+    input: &'a str,
+    answer: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    parse_ns: u64,
+    duration_ns: u64,
+}
+
+impl Serialize for PartResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PartJson {
+            day: self.day,
+            part: self.part,
+            input: &self.input,
+            answer: &self.answer,
+            text: self.text(),
+            parse_ns: self.parse.as_nanos() as u64,
+            duration_ns: self.duration.as_nanos() as u64,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Solve both parts of a day for one input, which is named `input` in the
+/// results.
+pub fn run_input(day: &dyn Day, input: &str, text: &str) -> [PartResult; 2] {
+    let (answers, timings) = day.solve_timed(text);
+    let mut answers = answers.into_iter();
+    [1, 2].map(|part| PartResult {
+        day: day.number(),
+        part,
+        input: input.to_string(),
+        answer: answers.next().unwrap(),
+        parse: timings.parse,
+        duration: timings.parts[usize::from(part) - 1],
+    })
+}
+
+/// Like `run_input`, but for input that may be malformed. The input is
+/// checked with `Day::try_solve` before the fast path is timed, so that
+/// malformed input is reported instead of causing a panic.
+pub fn try_run_input(
+    day: &dyn Day,
+    input: &str,
+    text: &str,
+) -> Result<[PartResult; 2], ParseError> {
+    // This solves the input twice on purpose. The checked parsers are
+    // slower than the fast path, so their timings would not be the ones the
+    // results are meant to show, and the fast path may only run once the
+    // input is known to be valid. The checked answers still catch a fast
+    // path that disagrees, in debug builds.
+    let checked = day.try_solve(text)?;
+    let results = run_input(day, input, text);
+    debug_assert_eq!(
+        checked,
+        results.each_ref().map(|result| result.answer.clone())
+    );
+    Ok(results)
+}
+
+/// Solve both parts of a day for all of its input files in `input_dir`.
+pub fn run_day(input_dir: &Path, day: &dyn Day) -> io::Result<Vec<PartResult>> {
+    let mut results = Vec::new();
+    for input in verify::input_files(input_dir, day.number())? {
+        let path = input_dir.join(&input);
+        let text = fs::read_to_string(&path)?;
+        results.extend(run_input(day, &path.display().to_string(), &text));
+    }
+    Ok(results)
+}
+
+/// All results as a JSON object with a `results` array.
+pub fn to_json(results: &[PartResult]) -> String {
+    #[derive(Serialize)]
+    struct Results<'a> {
+        results: &'a [PartResult],
+    }
+    serde_json::to_string_pretty(&Results { results }).expect("results are valid JSON")
+}
+
+#[test]
+fn test_run_input() {
+    let day = crate::solution::day(2).unwrap();
+    let [part_1, part_2] = run_input(day, "-", "A Y\nB X\nC Z\n");
+    assert_eq!((part_1.part, part_1.answer.as_str()), (1, "15"));
+    assert_eq!((part_2.part, part_2.answer.as_str()), (2, "12"));
+    assert_eq!(part_2.input, "-");
+    assert_eq!(part_1.parse, part_2.parse);
+}
+
+#[test]
+fn test_try_run_input() {
+    let day = crate::solution::day(2).unwrap();
+    let [part_1, _] = try_run_input(day, "-", "A Y\nB X\nC Z\n").unwrap();
+    assert_eq!(part_1.answer, "15");
+    let day = crate::solution::day(5).unwrap();
+    let input = include_str!("../input/2022/day1.txt");
+    assert!(try_run_input(day, "day1.txt", input).is_err());
+    let day = crate::solution::day(11).unwrap();
+    assert_eq!(
+        try_run_input(day, "-", "abc").unwrap_err().to_string(),
+        "line 1, column 1: expected \"Monkey \", found 'a'"
+    );
+}
+
+#[test]
+fn test_run_day() {
+    let input_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("input/2022");
+    let day = crate::solution::day(10).unwrap();
+    let results = run_day(&input_dir, day).unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!((results[0].part, results[1].part), (1, 2));
+    assert!(results[0].input.ends_with("day10.txt"));
+    assert!(results[3].input.ends_with("day10/example.txt"));
+    assert_eq!(results[0].answer, "13680");
+    assert_eq!(results[0].text(), None);
+    assert_eq!(results[1].text().as_deref(), Some("PZGPKPEB"));
+    assert_eq!(results[3].text(), None);
+
+    let json: serde_json::Value = serde_json::from_str(&to_json(&results)).unwrap();
+    let parts = json["results"].as_array().unwrap();
+    assert_eq!(parts.len(), 4);
+    assert_eq!(parts[1]["day"], 10);
+    assert_eq!(parts[1]["part"], 2);
+    assert_eq!(parts[1]["text"], "PZGPKPEB");
+    assert_eq!(parts[0].get("text"), None);
+    assert!(parts[0]["duration_ns"].is_u64());
+    assert!(to_json(&results).starts_with("{\n  \"results\": [\n    {\n      \"day\": 10,"));
+}
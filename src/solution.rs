@@ -0,0 +1,184 @@
+use std::{
+    any::type_name,
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::util::{normalize_input, ParseError};
+use crate::{day1, day10, day11, day12, day13, day2, day3, day4, day5, day6, day7, day8, day9};
+
+/// The solution to a single day. The input is parsed once and then shared by
+/// both parts. `parse` is given input whose line endings have been normalised
+/// by `normalize_input`, and may panic on malformed input. `try_part_1` and
+/// `try_part_2` take any input and check it as they parse.
+pub trait Solution {
+    const DAY: u8;
+    type Parsed<'a>;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Self::Parsed<'_>;
+    fn part_1(parsed: &Self::Parsed<'_>) -> Self::Answer1;
+    fn part_2(parsed: &Self::Parsed<'_>) -> Self::Answer2;
+    fn try_part_1(input: &str) -> Result<Self::Answer1, ParseError>;
+    fn try_part_2(input: &str) -> Result<Self::Answer2, ParseError>;
+}
+
+/// A `Solution` with its types erased, so that all days can be listed
+/// together.
+pub trait Day: Sync {
+    fn number(&self) -> u8;
+
+    /// Solve a part of the puzzle, or return `None` if there is no such part.
+    fn solve(&self, part: u8, input: &str) -> Option<String>;
+
+    /// Solve both parts, parsing the input only once.
+    fn solve_all(&self, input: &str) -> [String; 2];
+
+    /// Like `solve_all`, but also measures how long each phase takes.
+    fn solve_timed(&self, input: &str) -> ([String; 2], Timings);
+
+    /// Solve both parts of input that may be malformed, returning the first
+    /// error instead of panicking.
+    fn try_solve(&self, input: &str) -> Result<[String; 2], ParseError>;
+
+    /// The name of the type of the answer to a part.
+    fn answer_type(&self, part: u8) -> Option<&'static str>;
+}
+
+impl<S: Solution + Sync> Day for S {
+    fn number(&self) -> u8 {
+        S::DAY
+    }
+
+    fn solve(&self, part: u8, input: &str) -> Option<String> {
+        let input = normalize_input(input);
+        match part {
+            1 => Some(S::part_1(&S::parse(&input)).to_string()),
+            2 => Some(S::part_2(&S::parse(&input)).to_string()),
+            _ => None,
+        }
+    }
+
+    fn solve_all(&self, input: &str) -> [String; 2] {
+        let input = normalize_input(input);
+        let parsed = S::parse(&input);
+        [
+            S::part_1(&parsed).to_string(),
+            S::part_2(&parsed).to_string(),
+        ]
+    }
+
+    fn solve_timed(&self, input: &str) -> ([String; 2], Timings) {
+        let start = Instant::now();
+        let input = normalize_input(input);
+        let parsed = S::parse(&input);
+        let parse = start.elapsed();
+        let start = Instant::now();
+        let answer_1 = S::part_1(&parsed);
+        let part_1 = start.elapsed();
+        let start = Instant::now();
+        let answer_2 = S::part_2(&parsed);
+        let part_2 = start.elapsed();
+        (
+            [answer_1.to_string(), answer_2.to_string()],
+            Timings {
+                parse,
+                parts: [part_1, part_2],
+            },
+        )
+    }
+
+    fn try_solve(&self, input: &str) -> Result<[String; 2], ParseError> {
+        Ok([
+            S::try_part_1(input)?.to_string(),
+            S::try_part_2(input)?.to_string(),
+        ])
+    }
+
+    fn answer_type(&self, part: u8) -> Option<&'static str> {
+        match part {
+            1 => Some(type_name::<S::Answer1>()),
+            2 => Some(type_name::<S::Answer2>()),
+            _ => None,
+        }
+    }
+}
+
+/// How long the phases of a solution took. Parsing includes normalising the
+/// input, and formatting the answers is not included in any phase.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub parts: [Duration; 2],
+}
+
+/// All days, in order.
+pub static DAYS: [&dyn Day; 13] = [
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+];
+
+pub fn day(number: u8) -> Option<&'static dyn Day> {
+    DAYS.iter().copied().find(|day| day.number() == number)
+}
+
+#[test]
+fn test_days_are_in_order() {
+    for (index, day) in DAYS.iter().enumerate() {
+        assert_eq!(usize::from(day.number()), index + 1);
+    }
+    assert!(day(14).is_none());
+}
+
+#[test]
+fn test_solve_through_registry() {
+    let input = include_str!("../input/2022/day1.txt");
+    assert_eq!(day(1).unwrap().solve(1, input), Some("70374".to_string()));
+    assert_eq!(day(1).unwrap().solve(3, input), None);
+    let input = include_str!("../input/2022/day5.txt");
+    assert_eq!(
+        day(5).unwrap().solve(2, input),
+        Some(day5::part_2(input).to_string())
+    );
+    assert_eq!(
+        day(5).unwrap().solve_all(input),
+        [
+            day5::part_1(input).to_string(),
+            day5::part_2(input).to_string()
+        ]
+    );
+    let (answers, _) = day(5).unwrap().solve_timed(input);
+    assert_eq!(answers, day(5).unwrap().solve_all(input));
+    assert_eq!(day(5).unwrap().try_solve(input), Ok(answers));
+    assert_eq!(
+        day(10).unwrap().answer_type(2),
+        Some(type_name::<day10::Crt>())
+    );
+}
+
+#[test]
+fn test_try_solve() {
+    let input_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("input/2022");
+    for day in DAYS {
+        for name in crate::verify::input_files(&input_dir, day.number()).unwrap() {
+            let input = std::fs::read_to_string(input_dir.join(name)).unwrap();
+            assert_eq!(day.try_solve(&input), Ok(day.solve_all(&input)));
+        }
+    }
+    // Input for another day is an error rather than a panic
+    let input = include_str!("../input/2022/day1.txt");
+    assert!(day(5).unwrap().try_solve(input).is_err());
+    assert!(day(11).unwrap().try_solve("abc").is_err());
+}
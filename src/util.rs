@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 pub fn read_number_one_or_two_digits(bytes: &mut impl Iterator<Item = u8>) -> u8 {
     let mut num = bytes.next().unwrap() & 0b1111;
     if let Some(other_number) = bytes.next() {
@@ -13,3 +15,550 @@ pub fn read_number_one_or_two_digits(bytes: &mut impl Iterator<Item = u8>) -> u8
 pub fn read_two_digit_number(bytes: &mut impl Iterator<Item = u8>) -> u8 {
     (bytes.next().unwrap() & 0b1111) * 10 + (bytes.next().unwrap() & 0b1111)
 }
+
+/// Normalise input that was saved on Windows or copied from a browser: `\r\n`
+/// line endings become `\n`, and trailing whitespace is removed, keeping one
+/// final newline if there was one. Input that needs no changes is borrowed.
+pub fn normalize_input(s: &str) -> Cow<'_, str> {
+    let trimmed = s.trim_end();
+    let trailing = &s[trimmed.len()..];
+    let has_carriage_return = memchr::memchr(b'\r', trimmed.as_bytes()).is_some();
+    match (has_carriage_return, trailing.find('\n')) {
+        (false, None) => Cow::Borrowed(trimmed),
+        (false, Some(0)) => Cow::Borrowed(&s[..=trimmed.len()]),
+        (_, newline) => {
+            let mut normalized = trimmed.replace("\r\n", "\n");
+            if newline.is_some() {
+                normalized.push('\n');
+            }
+            Cow::Owned(normalized)
+        }
+    }
+}
+
+/// An error in the puzzle input. Lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Something else was found, or the input ended if `found` is `None`.
+    Expected {
+        what: &'static str,
+        found: Option<u8>,
+    },
+    /// Like `Expected`, but for literal text.
+    ExpectedText {
+        text: &'static str,
+        found: Option<u8>,
+    },
+    /// The input is well-formed, but does not describe a valid puzzle.
+    Invalid(&'static str),
+}
+
+impl ParseError {
+    /// Create an error at the given byte offset in the input.
+    pub fn at(input: &str, offset: usize, kind: ParseErrorKind) -> Self {
+        let before = &input.as_bytes()[..offset];
+        let line_start = memchr::memrchr(b'\n', before).map_or(0, |newline| newline + 1);
+        ParseError {
+            line: memchr::memchr_iter(b'\n', before).count() + 1,
+            column: offset - line_start + 1,
+            kind,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_found(f: &mut std::fmt::Formatter<'_>, found: Option<u8>) -> std::fmt::Result {
+            match found {
+                Some(b) => write!(f, ", found '{}'", b.escape_ascii()),
+                None => write!(f, ", found end of input"),
+            }
+        }
+
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match self.kind {
+            ParseErrorKind::Expected { what, found } => {
+                write!(f, "expected {what}")?;
+                write_found(f, found)
+            }
+            ParseErrorKind::ExpectedText { text, found } => {
+                write!(f, "expected {text:?}")?;
+                write_found(f, found)
+            }
+            ParseErrorKind::Invalid(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A cursor over the puzzle input, for parsers that report errors instead of
+/// panicking.
+#[derive(Clone, Debug)]
+pub struct Scanner<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Scanner { input, position: 0 }
+    }
+
+    /// The current byte offset in the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.position == self.input.len()
+    }
+
+    pub fn peek(&self) -> Option<u8> {
+        self.rest().first().copied()
+    }
+
+    /// The remaining input.
+    pub fn rest(&self) -> &'a [u8] {
+        &self.input.as_bytes()[self.position..]
+    }
+
+    /// An error at the given byte offset.
+    pub fn error_at(&self, offset: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError::at(self.input, offset, kind)
+    }
+
+    /// An error for finding something other than `what` at the current
+    /// position.
+    pub fn expected(&self, what: &'static str) -> ParseError {
+        self.error_at(
+            self.position,
+            ParseErrorKind::Expected {
+                what,
+                found: self.peek(),
+            },
+        )
+    }
+
+    /// An error for an invalid puzzle at the given byte offset.
+    pub fn invalid_at(&self, offset: usize, message: &'static str) -> ParseError {
+        self.error_at(offset, ParseErrorKind::Invalid(message))
+    }
+
+    /// Skip the given text.
+    pub fn tag(&mut self, text: &'static str) -> Result<(), ParseError> {
+        let matching = self
+            .rest()
+            .iter()
+            .zip(text.as_bytes())
+            .take_while(|(a, b)| a == b)
+            .count();
+        if matching == text.len() {
+            self.position += matching;
+            Ok(())
+        } else {
+            let offset = self.position + matching;
+            Err(self.error_at(
+                offset,
+                ParseErrorKind::ExpectedText {
+                    text,
+                    found: self.input.as_bytes().get(offset).copied(),
+                },
+            ))
+        }
+    }
+
+    /// Read a byte which should be one of `options`.
+    pub fn one_of(&mut self, options: &[u8], what: &'static str) -> Result<u8, ParseError> {
+        match self.peek() {
+            Some(b) if options.contains(&b) => {
+                self.position += 1;
+                Ok(b)
+            }
+            _ => Err(self.expected(what)),
+        }
+    }
+
+    /// Skip all bytes that satisfy the predicate, and return them.
+    pub fn take_while(&mut self, predicate: impl Fn(u8) -> bool) -> &'a [u8] {
+        let rest = self.rest();
+        let len = rest.iter().take_while(|b| predicate(**b)).count();
+        self.position += len;
+        &rest[..len]
+    }
+
+    /// Read an unsigned decimal number that fits in `T`. Leading zeros are
+    /// not allowed, as the fast parsers often expect numbers of a fixed width.
+    pub fn number<T: TryFrom<u64>>(&mut self) -> Result<T, ParseError> {
+        let start = self.position;
+        let digits = self.take_while(|b| b.is_ascii_digit());
+        if digits.is_empty() {
+            return Err(self.expected("digit"));
+        }
+        if digits.len() > 1 && digits[0] == b'0' {
+            return Err(self.invalid_at(start, "leading zero"));
+        }
+        digits
+            .iter()
+            .try_fold(0u64, |acc, d| {
+                acc.checked_mul(10)?.checked_add(u64::from(d & 0b1111))
+            })
+            .and_then(|number| T::try_from(number).ok())
+            .ok_or_else(|| self.invalid_at(start, "number out of range"))
+    }
+
+    /// Read a number of at most two digits, which is what the fast parsers
+    /// can read when a number can have one or two.
+    pub fn small_number(&mut self) -> Result<u8, ParseError> {
+        let start = self.position;
+        match self.number()? {
+            number @ 0..=99 => Ok(number),
+            _ => Err(self.invalid_at(start, "number out of range")),
+        }
+    }
+
+    /// Read a decimal number with an optional minus sign that fits in `T`.
+    pub fn signed_number<T: TryFrom<i64>>(&mut self) -> Result<T, ParseError> {
+        let start = self.position;
+        let negative = self.peek() == Some(b'-');
+        if negative {
+            self.position += 1;
+        }
+        let magnitude: i64 = self.number()?;
+        T::try_from(if negative { -magnitude } else { magnitude })
+            .map_err(|_| self.invalid_at(start, "number out of range"))
+    }
+
+    /// Skip a newline, which may be omitted at the end of the input.
+    pub fn line_end(&mut self) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(b'\n') => {
+                self.position += 1;
+                Ok(())
+            }
+            None => Ok(()),
+            Some(_) => Err(self.expected("newline")),
+        }
+    }
+}
+
+/// A direction on a [`Grid`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// A rectangular grid of cells, stored row by row. Consecutive rows may be
+/// separated by padding, such as the newlines in puzzle input, which is why
+/// the `stride` can be larger than the `width`. Positions on the grid are
+/// indices into the underlying cells, so buffers of the same layout can be
+/// indexed with them as well.
+#[derive(Copy, Clone, Debug)]
+pub struct Grid<'a, T> {
+    cells: &'a [T],
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl<'a> Grid<'a, u8> {
+    /// Wrap puzzle input consisting of rows separated by newlines. A trailing
+    /// newline is allowed. Panics if not all rows have the same length.
+    pub fn from_input(s: &'a str) -> Self {
+        let cells = s.as_bytes();
+        let cells = cells.strip_suffix(b"\n").unwrap_or(cells);
+        let width = memchr::memchr(b'\n', cells).unwrap_or(cells.len());
+        let mut height = 0;
+        for line in cells.split(|b| *b == b'\n') {
+            height += 1;
+            assert!(
+                line.len() == width,
+                "row {} has length {}, but the first row has length {}",
+                height,
+                line.len(),
+                width
+            );
+        }
+        Grid::new(cells, width, height, width + 1)
+    }
+
+    /// Like `from_input`, but returns an error for empty input and rows of
+    /// different lengths.
+    pub fn try_from_input(s: &'a str) -> Result<Self, ParseError> {
+        let cells = s.as_bytes();
+        let cells = cells.strip_suffix(b"\n").unwrap_or(cells);
+        let width = memchr::memchr(b'\n', cells).unwrap_or(cells.len());
+        if width == 0 {
+            return Err(ParseError::at(s, 0, ParseErrorKind::Invalid("empty row")));
+        }
+        let mut height = 0;
+        let mut row_start = 0;
+        for line in cells.split(|b| *b == b'\n') {
+            height += 1;
+            if line.len() != width {
+                let (offset, what) = if line.len() < width {
+                    (row_start + line.len(), "grid cell")
+                } else {
+                    (row_start + width, "newline")
+                };
+                let found = cells.get(offset).copied();
+                return Err(ParseError::at(
+                    s,
+                    offset,
+                    ParseErrorKind::Expected { what, found },
+                ));
+            }
+            row_start += width + 1;
+        }
+        Ok(Grid::new(cells, width, height, width + 1))
+    }
+}
+
+impl<'a, T> Grid<'a, T> {
+    /// Create a grid of the given size. Panics if there are not enough cells.
+    pub fn new(cells: &'a [T], width: usize, height: usize, stride: usize) -> Self {
+        assert!(width <= stride);
+        assert!(height == 0 || cells.len() >= stride * (height - 1) + width);
+        Grid {
+            cells,
+            width,
+            height,
+            stride,
+        }
+    }
+
+    pub fn cells(&self) -> &'a [T] {
+        self.cells
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Convert coordinates to a position.
+    pub fn position(&self, x: usize, y: usize) -> usize {
+        debug_assert!(x < self.width && y < self.height);
+        y * self.stride + x
+    }
+
+    /// Convert a position to coordinates.
+    pub fn coordinates(&self, position: usize) -> (usize, usize) {
+        (position % self.stride, position / self.stride)
+    }
+
+    /// All positions orthogonally adjacent to the given one.
+    pub fn neighbours(&self, position: usize) -> impl Iterator<Item = usize> {
+        let (x, y) = self.coordinates(position);
+        [
+            (x > 0).then(|| position - 1),
+            (x + 1 < self.width).then(|| position + 1),
+            (y > 0).then(|| position - self.stride),
+            (y + 1 < self.height).then(|| position + self.stride),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// All positions from the given one up to the edge of the grid in the
+    /// given direction, including the starting position.
+    pub fn ray(&self, position: usize, direction: Direction) -> Ray {
+        let (x, y) = self.coordinates(position);
+        let (step, remaining) = match direction {
+            Direction::Up => (self.stride.wrapping_neg(), y + 1),
+            Direction::Down => (self.stride, self.height - y),
+            Direction::Left => (usize::MAX, x + 1),
+            Direction::Right => (1, self.width - x),
+        };
+        Ray {
+            position,
+            step,
+            remaining,
+        }
+    }
+
+    /// All positions in a row, from left to right.
+    pub fn row(&self, y: usize) -> Ray {
+        self.ray(self.position(0, y), Direction::Right)
+    }
+
+    /// All positions in a column, from top to bottom.
+    pub fn column(&self, x: usize) -> Ray {
+        self.ray(self.position(x, 0), Direction::Down)
+    }
+}
+
+/// An iterator over positions on a straight line through a [`Grid`].
+#[derive(Clone, Debug)]
+pub struct Ray {
+    position: usize,
+    /// The step between positions, wrapping for negative directions.
+    step: usize,
+    remaining: usize,
+}
+
+impl Iterator for Ray {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let position = self.position;
+        self.position = self.position.wrapping_add(self.step);
+        self.remaining -= 1;
+        Some(position)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Ray {}
+
+impl<'a, T> std::ops::Index<usize> for Grid<'a, T> {
+    type Output = T;
+
+    fn index(&self, position: usize) -> &T {
+        &self.cells[position]
+    }
+}
+
+#[test]
+fn test_grid_from_input() {
+    let grid = Grid::from_input("abc\ndef\n");
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid.position(0, 1), 4);
+    assert_eq!(grid[grid.position(1, 1)], b'e');
+    assert_eq!(grid.coordinates(6), (2, 1));
+}
+
+#[test]
+fn test_grid_neighbours() {
+    let grid = Grid::from_input("abc\ndef\nghi");
+    let neighbours = |x, y| -> Vec<u8> {
+        grid.neighbours(grid.position(x, y))
+            .map(|position| grid[position])
+            .collect()
+    };
+    assert_eq!(neighbours(0, 0), b"bd");
+    assert_eq!(neighbours(1, 1), b"dfbh");
+    assert_eq!(neighbours(2, 2), b"hf");
+}
+
+#[test]
+fn test_grid_rays() {
+    let grid = Grid::from_input("abc\ndef\nghi");
+    let ray = |x, y, direction| -> Vec<u8> {
+        grid.ray(grid.position(x, y), direction)
+            .map(|position| grid[position])
+            .collect()
+    };
+    assert_eq!(ray(1, 1, Direction::Up), b"eb");
+    assert_eq!(ray(1, 1, Direction::Down), b"eh");
+    assert_eq!(ray(0, 2, Direction::Right), b"ghi");
+    assert_eq!(ray(0, 2, Direction::Left), b"g");
+    assert_eq!(grid.column(2).map(|p| grid[p]).collect::<Vec<_>>(), b"cfi");
+    assert_eq!(grid.row(1).map(|p| grid[p]).collect::<Vec<_>>(), b"def");
+}
+
+#[test]
+fn test_parse_error_position() {
+    let input = "ab\ncd\nef";
+    let error = ParseError::at(input, 4, ParseErrorKind::Invalid("bad"));
+    assert_eq!((error.line, error.column), (2, 2));
+    assert_eq!(error.to_string(), "line 2, column 2: bad");
+    let error = ParseError::at(input, 0, ParseErrorKind::Invalid("bad"));
+    assert_eq!((error.line, error.column), (1, 1));
+}
+
+/// The input as it would look when saved on Windows, with an extra blank line
+/// at the end.
+#[cfg(test)]
+pub fn windows_copy(s: &str) -> String {
+    format!("{}\r\n\r\n", s.trim_end().replace('\n', "\r\n"))
+}
+
+#[test]
+fn test_normalize_input() {
+    for (input, expected) in [
+        ("a\nb\n", "a\nb\n"),
+        ("a\nb", "a\nb"),
+        ("a\r\nb\r\n", "a\nb\n"),
+        ("a\r\nb", "a\nb"),
+        ("a\nb\n\n\n", "a\nb\n"),
+        ("a\nb \t\n", "a\nb\n"),
+        ("a\nb  ", "a\nb"),
+        ("  a\n b\n \r\n", "  a\n b\n"),
+        ("a\rb\n", "a\rb\n"),
+    ] {
+        assert_eq!(normalize_input(input), expected);
+    }
+    assert!(matches!(normalize_input("a\nb\n"), Cow::Borrowed(_)));
+    assert!(matches!(normalize_input("a\nb\n\n"), Cow::Borrowed(_)));
+}
+
+#[test]
+fn test_scanner() {
+    let mut scanner = Scanner::new("move 12 from -3\r\n");
+    scanner.tag("move ").unwrap();
+    assert_eq!(scanner.number::<u8>(), Ok(12));
+    assert_eq!(
+        scanner.tag(" to ").unwrap_err().to_string(),
+        "line 1, column 9: expected \" to \", found 'f'"
+    );
+    scanner.tag(" from ").unwrap();
+    assert_eq!(scanner.signed_number::<i8>(), Ok(-3));
+    assert_eq!(
+        scanner.line_end().unwrap_err().to_string(),
+        "line 1, column 16: expected newline, found '\\r'"
+    );
+    let mut scanner = Scanner::new("256");
+    assert_eq!(
+        scanner.number::<u8>().unwrap_err().to_string(),
+        "line 1, column 1: number out of range"
+    );
+    let number = |s| Scanner::new(s).number::<u32>().map_err(|e| e.to_string());
+    assert_eq!(number("0"), Ok(0));
+    assert_eq!(number("10"), Ok(10));
+    assert_eq!(
+        number("007"),
+        Err("line 1, column 1: leading zero".to_string())
+    );
+    let small = |s| Scanner::new(s).small_number().map_err(|e| e.to_string());
+    assert_eq!(small("99"), Ok(99));
+    assert_eq!(
+        small("100"),
+        Err("line 1, column 1: number out of range".to_string())
+    );
+}
+
+#[test]
+fn test_grid_try_from_input() {
+    assert!(Grid::try_from_input("ab\ncd\n").is_ok());
+    assert_eq!(
+        Grid::try_from_input("abc\nde\nfgh")
+            .unwrap_err()
+            .to_string(),
+        "line 2, column 3: expected grid cell, found '\\n'"
+    );
+    assert_eq!(
+        Grid::try_from_input("ab\ncd\nefg").unwrap_err().to_string(),
+        "line 3, column 3: expected newline, found 'g'"
+    );
+    assert!(Grid::try_from_input("").is_err());
+}
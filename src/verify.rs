@@ -0,0 +1,278 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::solution::{Day, DAYS};
+
+/// An error while loading the answers file.
+#[derive(Debug)]
+pub enum AnswersError {
+    Io(PathBuf, io::Error),
+    Toml(toml::de::Error),
+    /// The file is valid TOML, but is not laid out as expected.
+    Invalid(String),
+}
+
+impl Display for AnswersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnswersError::Io(path, error) => write!(f, "{}: {error}", path.display()),
+            AnswersError::Toml(error) => write!(f, "{error}"),
+            AnswersError::Invalid(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+/// The expected answers by day, input file and part, as recorded in a file
+/// like `answers/2022.toml`:
+///
+/// ```toml
+/// [day1."day1.txt"]
+/// part_1 = 70374
+/// part_2 = 204610
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers(BTreeMap<(u8, String), [Option<String>; 2]>);
+
+impl Answers {
+    pub fn parse(s: &str) -> Result<Self, AnswersError> {
+        let invalid = |message: String| AnswersError::Invalid(message);
+        let days: toml::value::Table = toml::from_str(s).map_err(AnswersError::Toml)?;
+        let mut answers = Answers::default();
+        for (day, inputs) in days {
+            let number = day
+                .strip_prefix("day")
+                .and_then(|number| number.parse().ok())
+                .ok_or_else(|| invalid(format!("`{day}` is not a day")))?;
+            let inputs = inputs
+                .as_table()
+                .ok_or_else(|| invalid(format!("`{day}` is not a table of input files")))?;
+            for (input, parts) in inputs {
+                let parts = parts
+                    .as_table()
+                    .ok_or_else(|| invalid(format!("`{day}.{input}` is not a table of parts")))?;
+                let mut expected = [None, None];
+                for (part, answer) in parts {
+                    let index = match part.as_str() {
+                        "part_1" => 0,
+                        "part_2" => 1,
+                        _ => return Err(invalid(format!("unknown part `{day}.{input}.{part}`"))),
+                    };
+                    expected[index] = Some(match answer {
+                        toml::Value::String(answer) => answer.clone(),
+                        toml::Value::Integer(answer) => answer.to_string(),
+                        _ => {
+                            return Err(invalid(format!(
+                                "`{day}.{input}.{part}` is not a string or an integer"
+                            )))
+                        }
+                    });
+                }
+                answers.0.insert((number, input.clone()), expected);
+            }
+        }
+        Ok(answers)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        let s = fs::read_to_string(path).map_err(|error| AnswersError::Io(path.into(), error))?;
+        Answers::parse(&s)
+    }
+
+    /// The recorded answer to a part for an input file.
+    pub fn get(&self, day: u8, input: &str, part: u8) -> Option<&str> {
+        let parts = self.0.get(&(day, input.to_string()))?;
+        parts.get(usize::from(part).checked_sub(1)?)?.as_deref()
+    }
+}
+
+/// The input files for a day, relative to the input directory: `dayN.txt`,
+/// followed by any `dayN/<name>.txt` in order of name.
+pub fn input_files(input_dir: &Path, day: u8) -> io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    let name = format!("day{day}.txt");
+    if input_dir.join(&name).is_file() {
+        files.push(name);
+    }
+    let dir = input_dir.join(format!("day{day}"));
+    if dir.is_dir() {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().is_some_and(|extension| extension == "txt") {
+                if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+                    names.push(format!("day{day}/{name}"));
+                }
+            }
+        }
+        names.sort();
+        files.extend(names);
+    }
+    Ok(files)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// No answer was recorded for this input.
+    Missing,
+}
+
+/// The result of solving a part for one input file.
+#[derive(Debug, Clone)]
+pub struct Verification {
+    pub day: u8,
+    pub input: String,
+    pub part: u8,
+    pub answer: String,
+    pub outcome: Outcome,
+}
+
+/// Compare an answer with the recorded one. Trailing whitespace is ignored,
+/// so that multi-line answers can be written naturally.
+fn outcome(answer: &str, expected: Option<&str>) -> Outcome {
+    match expected {
+        None => Outcome::Missing,
+        Some(expected) if expected.trim_end() == answer.trim_end() => Outcome::Pass,
+        Some(expected) => Outcome::Fail {
+            expected: expected.to_string(),
+        },
+    }
+}
+
+/// Solve both parts of every day for all of its input files in `input_dir`,
+/// and compare the results with the recorded answers.
+pub fn verify(answers: &Answers, input_dir: &Path) -> io::Result<Vec<Verification>> {
+    let mut verifications = Vec::new();
+    for day in DAYS {
+        verifications.extend(verify_day(answers, input_dir, day)?);
+    }
+    Ok(verifications)
+}
+
+/// Solve both parts of a day for all of its input files in `input_dir`.
+pub fn verify_day(
+    answers: &Answers,
+    input_dir: &Path,
+    day: &dyn Day,
+) -> io::Result<Vec<Verification>> {
+    let mut verifications = Vec::new();
+    for input in input_files(input_dir, day.number())? {
+        let text = fs::read_to_string(input_dir.join(&input))?;
+        for (part, answer) in (1..=2).zip(day.solve_all(&text)) {
+            verifications.push(Verification {
+                day: day.number(),
+                outcome: outcome(&answer, answers.get(day.number(), &input, part)),
+                input: input.clone(),
+                part,
+                answer,
+            });
+        }
+    }
+    Ok(verifications)
+}
+
+/// Check both parts of a day against the recorded answer for every one of its
+/// input files. A missing answer counts as a failure.
+#[cfg(test)]
+pub fn assert_recorded_answers(day: u8) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let answers = Answers::load(&root.join("answers/2022.toml")).unwrap();
+    let day = crate::solution::day(day).unwrap();
+    let verifications = verify_day(&answers, &root.join("input/2022"), day).unwrap();
+    assert!(
+        !verifications.is_empty(),
+        "no inputs for day {}",
+        day.number()
+    );
+    for Verification {
+        input,
+        part,
+        answer,
+        outcome,
+        ..
+    } in verifications
+    {
+        match outcome {
+            Outcome::Pass => {}
+            Outcome::Fail { expected } => {
+                panic!("{input} part {part}: expected {expected}, got {answer}")
+            }
+            Outcome::Missing => panic!("{input} part {part}: no recorded answer, got {answer}"),
+        }
+    }
+}
+
+#[test]
+fn test_parse_answers() {
+    let answers = Answers::parse(
+        r#"
+[day5."day5.txt"]
+part_1 = "CMZ"
+
+[day10."day10.txt"]
+part_1 = 13140
+part_2 = '''
+##..
+..##
+'''
+"#,
+    )
+    .unwrap();
+    assert_eq!(answers.get(5, "day5.txt", 1), Some("CMZ"));
+    assert_eq!(answers.get(5, "day5.txt", 2), None);
+    assert_eq!(answers.get(5, "other.txt", 1), None);
+    assert_eq!(answers.get(10, "day10.txt", 1), Some("13140"));
+    assert_eq!(answers.get(10, "day10.txt", 2), Some("##..\n..##\n"));
+    assert_eq!(answers.get(10, "day10.txt", 3), None);
+}
+
+#[test]
+fn test_parse_invalid_answers() {
+    let error = |s| Answers::parse(s).unwrap_err().to_string();
+    assert_eq!(error("[days]"), "`days` is not a day");
+    assert_eq!(error("day1 = 3"), "`day1` is not a table of input files");
+    assert_eq!(
+        error("[day1.\"day1.txt\"]\npart_3 = 1"),
+        "unknown part `day1.day1.txt.part_3`"
+    );
+    assert_eq!(
+        error("[day1.\"day1.txt\"]\npart_1 = 1.5"),
+        "`day1.day1.txt.part_1` is not a string or an integer"
+    );
+    assert!(matches!(
+        Answers::parse("[day1"),
+        Err(AnswersError::Toml(_))
+    ));
+}
+
+#[test]
+fn test_outcome() {
+    assert_eq!(outcome("24000", Some("24000")), Outcome::Pass);
+    assert_eq!(outcome("#.\n.#\n", Some("#.\n.#")), Outcome::Pass);
+    assert_eq!(
+        outcome("24000", Some("45000")),
+        Outcome::Fail {
+            expected: "45000".to_string()
+        }
+    );
+    assert_eq!(outcome("24000", None), Outcome::Missing);
+}
+
+#[test]
+fn test_input_files() {
+    let input_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("input/2022");
+    assert_eq!(
+        input_files(&input_dir, 5).unwrap(),
+        ["day5.txt", "day5/example.txt"]
+    );
+    assert!(input_files(&input_dir, 26).unwrap().is_empty());
+}
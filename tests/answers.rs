@@ -0,0 +1,20 @@
+use std::path::Path;
+
+use advent_of_code_2022::verify::{self, Answers, Outcome};
+
+#[test]
+fn test_recorded_answers() {
+    let answers = Answers::load(Path::new("answers/2022.toml")).unwrap();
+    let verifications = verify::verify(&answers, Path::new("input/2022")).unwrap();
+    assert!(!verifications.is_empty());
+    for verification in verifications {
+        assert_eq!(
+            verification.outcome,
+            Outcome::Pass,
+            "day {} part {} for {}",
+            verification.day,
+            verification.part,
+            verification.input
+        );
+    }
+}
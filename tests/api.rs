@@ -0,0 +1,90 @@
+use std::cmp::Ordering;
+
+use advent_of_code_2022::{
+    day10::{self, Cpu, Instruction},
+    day13, day5,
+    day7::{FileTree, Path},
+    day9::{self, Direction, Rope},
+    solution::{self, DAYS},
+    util::{normalize_input, Grid},
+};
+
+#[test]
+fn test_every_day_is_registered() {
+    let numbers: Vec<_> = DAYS.iter().map(|day| day.number()).collect();
+    assert_eq!(numbers, (1..=13).collect::<Vec<_>>());
+    let day = solution::day(2).unwrap();
+    assert_eq!(day.solve(1, "A Y\nB X\nC Z\n"), Some("15".to_string()));
+    assert_eq!(day.solve(2, "A Y\r\nB X\r\nC Z"), Some("12".to_string()));
+}
+
+#[test]
+fn test_warehouse() {
+    let drawing = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n";
+    let mut lines = drawing.split_terminator('\n').map(str::as_bytes);
+    let (mut warehouse, stacks) = day5::Warehouse::<3>::parse(&mut lines);
+    assert_eq!(stacks, 3);
+    warehouse.move_multiple_crates(2, 1, 2);
+    // Crates are stored as letters counting from 1.
+    assert_eq!(warehouse.pop_crate(2), b'D' & 0b1_1111);
+    assert_eq!(warehouse.pop_crate(2), b'C' & 0b1_1111);
+    assert_eq!(warehouse.pop_crate(2), b'P' & 0b1_1111);
+    assert_eq!(warehouse.pop_crate(2), 0);
+}
+
+#[test]
+fn test_file_tree() {
+    let input = "$ cd /\n$ ls\ndir a\n100 b\n$ cd a\n$ ls\n200000 c\n";
+    let tree = FileTree::build(input);
+    assert_eq!(tree.total_size_used(), 200100);
+    assert_eq!(tree.total_size_of_small_directories(), 0);
+    assert_eq!(
+        tree.smallest_subdirectory_above_threshold(&mut Path::default(), 1000),
+        Some(200000)
+    );
+    assert!(FileTree::try_build("$ cd /\n$ ls\n12x b\n").is_err());
+}
+
+#[test]
+fn test_cpu() {
+    let mut cpu = Cpu::default();
+    let registers: Vec<_> = cpu
+        .execute_all_cycles(day10::parse_instructions("noop\naddx 3\naddx -5\n"))
+        .map(Cpu::register)
+        .collect();
+    assert_eq!(registers, [1, 1, 1, 4, 4, -1]);
+    assert!(matches!(
+        day10::try_parse_instructions("addx -7").unwrap()[..],
+        [Instruction::AddX(-7)]
+    ));
+}
+
+#[test]
+fn test_rope() {
+    let mut rope = Rope::<2>::default();
+    for _ in 0..3 {
+        rope.move_in_direction(Direction::Right);
+    }
+    assert_eq!(rope.tail_position().horizontal_diff, 2);
+    assert_eq!(day9::simulate_rope::<2>("R 4\nU 4\nL 3\nD 1\n"), 9);
+}
+
+#[test]
+fn test_packet_comparison() {
+    assert_eq!(day13::compare(b"[1,[2,3]]", b"[[1],4]"), Ordering::Less);
+    assert_eq!(day13::compare(b"[100]", b"[[99]]"), Ordering::Greater);
+}
+
+#[test]
+fn test_grid() {
+    let input = normalize_input("ab\r\ncd\r\n");
+    let grid = Grid::from_input(&input);
+    assert_eq!((grid.width(), grid.height()), (2, 2));
+    assert_eq!(grid[grid.position(1, 1)], b'd');
+}
+
+#[test]
+fn test_parse_errors_are_public() {
+    let error = day10::try_part_1("noop\nadx 3\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 3));
+}
@@ -0,0 +1,121 @@
+//! Compare the optimised solutions with the reference solutions on generated
+//! input. When they disagree, the input is shrunk to as few lines as possible
+//! before the test fails.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use advent_of_code_2022::{
+    day1, day10, day11, day12, day13, day2, day3, day4, day5, day6, day7, day8, day9, generate,
+    reference, solution,
+};
+
+const SEEDS: u64 = 16;
+const SIZES: [usize; 4] = [1, 4, 16, 64];
+
+/// How the optimised solutions disagree with the reference on an input, if
+/// they do.
+fn disagreement(day: u8, input: &str) -> Option<String> {
+    let Ok(expected) = panic::catch_unwind(|| reference::solve(day, input).unwrap()) else {
+        return Some("the reference solution panicked".to_string());
+    };
+    let day = solution::day(day).unwrap();
+    for (part, expected) in (1..=2).zip(expected) {
+        match panic::catch_unwind(AssertUnwindSafe(|| day.solve(part, input).unwrap())) {
+            Ok(answer) if answer.trim_end() == expected.trim_end() => {}
+            Ok(answer) => {
+                return Some(format!(
+                    "part {part} is {answer:?}, but should be {expected:?}"
+                ))
+            }
+            Err(_) => return Some(format!("part {part} panicked")),
+        }
+    }
+    None
+}
+
+/// Remove as many lines from the input as possible, while it keeps failing.
+/// Lines are removed in chunks, which get smaller until single lines are
+/// tried.
+fn shrink(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let join =
+        |lines: &[&str]| -> String { lines.iter().map(|line| format!("{line}\n")).collect() };
+    let mut lines: Vec<&str> = input.lines().collect();
+    let mut chunk = lines.len().div_ceil(2);
+    while chunk > 0 {
+        let mut start = 0;
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate: Vec<&str> = [&lines[..start], &lines[end..]].concat();
+            if fails(&join(&candidate)) {
+                lines = candidate;
+            } else {
+                start = end;
+            }
+        }
+        chunk /= 2;
+    }
+    join(&lines)
+}
+
+/// Check a day on generated input of every seed and size.
+fn check(day: u8, is_valid: fn(&str) -> bool) {
+    for size in SIZES {
+        for seed in 0..SEEDS {
+            let input = generate::generate(day, seed, size).unwrap();
+            assert!(is_valid(&input), "generated invalid input:\n{input}");
+            if disagreement(day, &input).is_some() {
+                // Only inputs that the checked parsers accept are kept, as
+                // the optimised solutions may assume that the input is valid.
+                let shrunk = shrink(&input, |input| {
+                    panic::catch_unwind(|| is_valid(input)).unwrap_or(false)
+                        && disagreement(day, input).is_some()
+                });
+                panic!(
+                    "day {day} with seed {seed} and size {size}: {}, for input:\n{shrunk}",
+                    disagreement(day, &shrunk).unwrap()
+                );
+            }
+        }
+    }
+}
+
+macro_rules! differential_tests {
+    ($($day:ident = $number:literal),* $(,)?) => {$(
+        #[test]
+        fn $day() {
+            check($number, |input| {
+                $day::try_part_1(input).is_ok() && $day::try_part_2(input).is_ok()
+            });
+        }
+    )*};
+}
+
+differential_tests! {
+    day1 = 1,
+    day2 = 2,
+    day3 = 3,
+    day4 = 4,
+    day5 = 5,
+    day6 = 6,
+    day7 = 7,
+    day8 = 8,
+    day9 = 9,
+    day10 = 10,
+    day11 = 11,
+    day12 = 12,
+    day13 = 13,
+}
+
+#[test]
+fn test_shrink() {
+    let input = "1\n2\n3\n4\n5\n6\n7\n";
+    let sum = |input: &str| {
+        input
+            .lines()
+            .map(|line| line.parse::<u32>().unwrap())
+            .sum::<u32>()
+    };
+    assert_eq!(shrink(input, |input| input.contains('5')), "5\n");
+    assert_eq!(shrink(input, |input| sum(input) >= 13), "6\n7\n");
+    assert_eq!(shrink(input, |_| false), input);
+}
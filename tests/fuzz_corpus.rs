@@ -0,0 +1,45 @@
+//! Run the checked-in fuzzing corpus through the same checks as the fuzz
+//! targets in `fuzz/`, so that they are also covered on stable.
+
+use std::{fs, path::Path};
+
+use advent_of_code_2022::{
+    day1, day10, day11, day12, day13, day2, day3, day4, day5, day6, day7, day8, day9,
+};
+
+/// The contents of every file in the corpus of a fuzz target.
+fn corpus(target: &str) -> Vec<String> {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fuzz/corpus")
+        .join(target);
+    let mut files: Vec<_> = fs::read_dir(&directory)
+        .unwrap_or_else(|error| panic!("could not read {}: {error}", directory.display()))
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "{} is empty", directory.display());
+    files
+        .iter()
+        .map(|file| fs::read_to_string(file).unwrap())
+        .collect()
+}
+
+macro_rules! corpus_tests {
+    ($($day:ident),* $(,)?) => {$(
+        #[test]
+        fn $day() {
+            for input in corpus(stringify!($day)) {
+                if let Ok(answer) = $day::try_part_1(&input) {
+                    assert_eq!(answer.to_string(), $day::part_1(&input).to_string());
+                }
+                if let Ok(answer) = $day::try_part_2(&input) {
+                    assert_eq!(answer.to_string(), $day::part_2(&input).to_string());
+                }
+            }
+        }
+    )*};
+}
+
+corpus_tests! {
+    day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13,
+}